@@ -0,0 +1,246 @@
+use crate::runner::{Run, Status, describe, draw_columns, json_string};
+use crate::shared::*;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::PathBuf;
+
+// The accepted answers for a day, as recorded in answers/{day}.toml:
+//
+//     part1 = "1234"
+//     part2 = "5678"
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    pub fn from_str(s: &str) -> Result<Answers> {
+        let mut answers = Answers::default();
+
+        for (n, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return err(&format!("line {}: expected key = value", n + 1));
+            };
+
+            let value = value.trim();
+            let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                Some(quoted) => quoted.to_owned(),
+                None if value.chars().all(|c| c.is_ascii_digit() || c == '-') => value.to_owned(),
+                None => return err(&format!("line {}: invalid value {value}", n + 1)),
+            };
+
+            match key.trim() {
+                "part1" => answers.part1 = Some(value),
+                "part2" => answers.part2 = Some(value),
+                key => return err(&format!("line {}: unknown key {key}", n + 1)),
+            }
+        }
+
+        Ok(answers)
+    }
+
+    pub fn draw(&self) -> String {
+        let mut str = String::new();
+
+        for (key, value) in [("part1", &self.part1), ("part2", &self.part2)] {
+            if let Some(value) = value {
+                str.push_str(&format!("{key} = \"{value}\"\n"));
+            }
+        }
+
+        str
+    }
+
+    pub fn get(&self, part: u32) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, part: u32, answer: &str) {
+        match part {
+            1 => self.part1 = Some(answer.to_owned()),
+            2 => self.part2 = Some(answer.to_owned()),
+            _ => panic!("invalid part number {part}"),
+        }
+    }
+}
+
+pub fn answers_path(day: u32) -> PathBuf {
+    PathBuf::from("answers").join(format!("{day}.toml"))
+}
+
+// Loads the recorded answers for a day. A day with no answers file has no answers.
+pub fn load(day: u32) -> Result<Answers> {
+    let path = answers_path(day);
+    if !path.exists() {
+        return Ok(Answers::default());
+    }
+
+    match read_to_string(&path) {
+        Ok(s) => Answers::from_str(&s).map_err(|e| Error::from(format!("{}: {e}", path.display()))),
+        Err(e) => err(&format!("failed to read file {}: {e}", path.display())),
+    }
+}
+
+pub fn record(day: u32, part: u32, answer: &str) -> Result<()> {
+    let mut answers = load(day)?;
+    answers.set(part, answer);
+
+    let path = answers_path(day);
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir)?;
+    }
+    write(&path, answers.draw())?;
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    Missing,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => f.write_str("pass"),
+            Verdict::Fail => f.write_str("FAIL"),
+            Verdict::Missing => f.write_str("missing"),
+        }
+    }
+}
+
+// Compares a run against the recorded answer. A run that errors or panics has failed, whether or
+// not an answer is recorded; otherwise a run with nothing recorded is missing.
+pub fn check(run: &Run, answers: &Answers) -> Verdict {
+    if run.failed() {
+        return Verdict::Fail;
+    }
+
+    match (answers.get(run.part), &run.answer) {
+        (None, _) => Verdict::Missing,
+        (Some(expected), Some(answer)) if expected == answer => Verdict::Pass,
+        (Some(_), _) => Verdict::Fail,
+    }
+}
+
+pub fn draw_verdicts(checked: &[(Run, Option<String>, Verdict)]) -> String {
+    let rows: Vec<Vec<String>> = checked
+        .iter()
+        .map(|(run, expected, verdict)| {
+            let answer = match run.status {
+                Status::Ok => run.answer.clone().unwrap_or_default(),
+                _ => describe(run),
+            };
+            vec![
+                run.day.to_string(),
+                run.part.to_string(),
+                verdict.to_string(),
+                answer,
+                expected.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    draw_columns(&["day", "part", "result", "answer", "expected"], &rows)
+}
+
+// Encodes a checked run as a single line JSON object, with the run itself nested inside.
+pub fn verdict_json(run: &Run, expected: Option<&str>, verdict: &Verdict) -> String {
+    format!(
+        "{{\"result\":{},\"expected\":{},\"run\":{}}}",
+        json_string(&verdict.to_string()),
+        expected
+            .map(json_string)
+            .unwrap_or_else(|| "null".to_owned()),
+        run.to_json()
+    )
+}
+
+#[test]
+fn test_answers_from_str() {
+    let answers = Answers::from_str("# day 4\npart1 = \"13\"\npart2 = 43\n").unwrap();
+    assert_eq!(Some("13"), answers.get(1));
+    assert_eq!(Some("43"), answers.get(2));
+
+    assert_eq!(Ok(Answers::default()), Answers::from_str(""));
+    assert!(Answers::from_str("part3 = \"1\"").is_err());
+    assert!(Answers::from_str("part1 = abc").is_err());
+}
+
+#[test]
+fn test_answers_roundtrip() {
+    let mut answers = Answers::default();
+    answers.set(2, "hello");
+
+    assert_eq!("part2 = \"hello\"\n", answers.draw());
+    assert_eq!(Ok(answers), Answers::from_str("part2 = \"hello\"\n"));
+}
+
+#[test]
+fn test_check() {
+    let answers = Answers::from_str("part1 = \"13\"").unwrap();
+    let run = |part, status, answer: Option<&str>| Run {
+        day: 4,
+        part,
+        answer: answer.map(str::to_owned),
+        status,
+        elapsed: std::time::Duration::ZERO,
+        load: std::time::Duration::ZERO,
+        input_hash: None,
+    };
+
+    assert_eq!(
+        Verdict::Pass,
+        check(&run(1, Status::Ok, Some("13")), &answers)
+    );
+    assert_eq!(
+        Verdict::Fail,
+        check(&run(1, Status::Ok, Some("14")), &answers)
+    );
+    assert_eq!(
+        Verdict::Missing,
+        check(&run(2, Status::Ok, Some("1")), &answers)
+    );
+    assert_eq!(
+        Verdict::Missing,
+        check(&run(2, Status::NotImplemented, None), &answers)
+    );
+
+    // Errors and panics fail even with nothing recorded to compare against.
+    let error = Status::Error("failed to read file".to_owned());
+    assert_eq!(Verdict::Fail, check(&run(2, error, None), &answers));
+    let panic = Status::Panicked("oops".to_owned());
+    assert_eq!(Verdict::Fail, check(&run(2, panic, None), &answers));
+}
+
+#[test]
+fn test_verdict_json() {
+    let run = Run {
+        day: 4,
+        part: 1,
+        answer: Some("14".to_owned()),
+        status: Status::Ok,
+        elapsed: std::time::Duration::ZERO,
+        load: std::time::Duration::ZERO,
+        input_hash: None,
+    };
+
+    assert_eq!(
+        format!(
+            r#"{{"result":"FAIL","expected":"13","run":{}}}"#,
+            run.to_json()
+        ),
+        verdict_json(&run, Some("13"), &Verdict::Fail)
+    );
+}
@@ -3,21 +3,21 @@ use crate::shared::*;
 pub struct Day01;
 
 impl Solution for Day01 {
-    fn part1(&self) -> Result<String> {
+    fn part1(&self, input: &str) -> Result<String> {
         let mut dial = Dial::new();
 
-        for line in input_lines(1)? {
-            _ = dial.adjust(line?.trim())?
+        for line in input.lines() {
+            _ = dial.adjust(line.trim())?
         }
 
         return Ok(dial.final_zeroes.to_string());
     }
 
-    fn part2(&self) -> Result<String> {
+    fn part2(&self, input: &str) -> Result<String> {
         let mut dial = Dial::new();
 
-        for line in input_lines(1)? {
-            _ = dial.adjust(line?.trim())?
+        for line in input.lines() {
+            _ = dial.adjust(line.trim())?
         }
 
         return Ok(dial.all_zeroes.to_string());
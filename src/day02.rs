@@ -5,18 +5,14 @@ use crate::shared::*;
 pub struct Day02;
 
 impl Solution for Day02 {
-    fn part1(&self) -> Result<String> {
-        let input = input(2)?;
-
-        let result = solve(parse_ranges(&input)?, is_invalid_id_p1);
+    fn part1(&self, input: &str) -> Result<String> {
+        let result = solve(parse_ranges(input)?, is_invalid_id_p1);
 
         return Ok(result.to_string());
     }
 
-    fn part2(&self) -> Result<String> {
-        let input = input(2)?;
-
-        let result = solve(parse_ranges(&input)?, is_invalid_id_p2);
+    fn part2(&self, input: &str) -> Result<String> {
+        let result = solve(parse_ranges(input)?, is_invalid_id_p2);
 
         return Ok(result.to_string());
     }
@@ -43,7 +39,7 @@ fn is_invalid_id_p1(n: usize) -> bool {
         return false;
     }
 
-    let power = 10usize.pow((digits / 2) as u32 as u32);
+    let power = 10usize.pow((digits / 2) as u32);
     let lower_half = n % power;
     let upper_half = (n - lower_half) / power;
 
@@ -55,7 +51,7 @@ fn is_invalid_id_p2(n: usize) -> bool {
     'next_window: for window_size in 1..=(digits.len / 2) {
         // Only look at evenly distributed windows (i.e., where window size is a valid denominator
         // of the number of digits.)
-        if digits.len % window_size != 0 {
+        if !digits.len.is_multiple_of(window_size) {
             continue;
         }
 
@@ -87,12 +83,12 @@ fn test_is_invalid_id_p2() {
         2121212121,
     ];
     for n in invalid {
-        assert_eq!(true, is_invalid_id_p2(n), "{n} should be an invalid id");
+        assert!(is_invalid_id_p2(n), "{n} should be an invalid id");
     }
 
     let valid = [0, 9, 981278309, 82173, 781297, 123, 43210, 100000000001];
     for n in valid {
-        assert_eq!(false, is_invalid_id_p2(n), "{n} should be a valid id");
+        assert!(!is_invalid_id_p2(n), "{n} should be a valid id");
     }
 }
 
@@ -122,10 +118,10 @@ fn parse_ranges(s: &str) -> Result<Vec<RangeInclusive<usize>>> {
 
 #[test]
 fn test_parse_ranges() {
-    let input = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
+    let input = crate::examples::load(2, "example").unwrap();
 
     assert_eq!(
-        parse_ranges(input).expect("should not return error"),
+        parse_ranges(&input).expect("should not return error"),
         vec![
             11..=22,
             95..=115,
@@ -141,23 +137,3 @@ fn test_parse_ranges() {
         ]
     )
 }
-
-#[test]
-fn test_example_part1() {
-    let input = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
-
-    assert_eq!(
-        1227775554,
-        solve(parse_ranges(input).unwrap(), is_invalid_id_p1)
-    );
-}
-
-#[test]
-fn test_example_part2() {
-    let input = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
-
-    assert_eq!(
-        4174379265,
-        solve(parse_ranges(input).unwrap(), is_invalid_id_p2)
-    );
-}
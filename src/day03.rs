@@ -3,22 +3,22 @@ use crate::shared::*;
 pub struct Day03;
 
 impl Solution for Day03 {
-    fn part1(&self) -> Result<String> {
+    fn part1(&self, input: &str) -> Result<String> {
         let mut sum = 0;
 
-        for line in input_lines(3)? {
-            let b = Bank::of(line?.trim())?;
+        for line in input.lines() {
+            let b = Bank::of(line.trim())?;
             sum += best_joltage_rating(&b, 2);
         }
 
         return Ok(sum.to_string());
     }
 
-    fn part2(&self) -> Result<String> {
+    fn part2(&self, input: &str) -> Result<String> {
         let mut sum = 0;
 
-        for line in input_lines(3)? {
-            let b = Bank::of(line?.trim())?;
+        for line in input.lines() {
+            let b = Bank::of(line.trim())?;
             sum += best_joltage_rating(&b, 12);
         }
 
@@ -59,18 +59,16 @@ fn best_joltage_rating(b: &Bank, digits: usize) -> usize {
     let mut start_idx = 0usize;
 
     // And the latest?
-    let mut end_idx = b.values.len() - digits;
+    let first_end_idx = b.values.len() - digits;
 
     let mut joltage = 0;
 
-    for i in 0..digits {
+    for (i, end_idx) in (first_end_idx..).enumerate().take(digits) {
         let selected_idx = start_idx + highest_leftmost_position(&b.values[start_idx..=end_idx]);
         start_idx = selected_idx + 1;
 
         let power = 10usize.pow(digits as u32 - i as u32 - 1);
         joltage += b.values[selected_idx] as usize * power;
-
-        end_idx += 1;
     }
 
     return joltage;
@@ -79,9 +77,9 @@ fn best_joltage_rating(b: &Bank, digits: usize) -> usize {
 fn highest_leftmost_position(values: &[u8]) -> usize {
     let mut best = (0, 0); /* idx, value */
 
-    for i in 0..values.len() {
-        if values[i] > best.1 {
-            best = (i, values[i]);
+    for (i, &value) in values.iter().enumerate() {
+        if value > best.1 {
+            best = (i, value);
         }
     }
 
@@ -5,20 +5,36 @@ pub struct Day04;
 type Map = MetaGrid<usize>;
 
 impl Solution for Day04 {
-    fn part1(&self) -> Result<String> {
-        let mut map = MetaGrid::from_file("inputs/4.txt")?;
+    fn part1(&self, input: &str) -> Result<String> {
+        let mut map = MetaGrid::from_str(input)?;
 
         count_adj_rolls(&mut map);
 
         return Ok(n_accessible_rolls(&map).to_string());
     }
 
-    fn part2(&self) -> Result<String> {
-        let mut map = MetaGrid::from_file("inputs/4.txt")?;
+    fn part2(&self, input: &str) -> Result<String> {
+        // Each generation removes every roll with fewer than four neighbouring rolls. Only rolls
+        // next to those just removed can become removable next.
+        let mut rolls = Automaton::new(
+            CharGrid::from_str(input)?,
+            |c| c == '@',
+            |c, n| match c {
+                '@' if n < 4 => '.',
+                _ => c,
+            },
+        )
+        .frontier();
 
         let mut total_removed = 0;
-        while let Some(n_removed) = remove_rolls(&mut map) {
+        visualize::record_frame(rolls.grid());
+        loop {
+            let n_removed = rolls.step();
+            if n_removed == 0 {
+                break;
+            }
             total_removed += n_removed;
+            visualize::record_frame(rolls.grid());
         }
 
         return Ok(total_removed.to_string());
@@ -28,10 +44,8 @@ impl Solution for Day04 {
 fn count_adj_rolls(map: &mut Map) {
     for pos in map.find_all('@') {
         let nearby_rolls = map
-            .adjacent(pos)
-            .iter()
-            .filter(|(_, c, _)| *c == '@')
-            .count();
+            .chars()
+            .count_neighbours(pos, Neighbourhood::Eight, |c| c == '@');
 
         map.set_meta(pos, nearby_rolls);
     }
@@ -43,67 +57,3 @@ fn n_accessible_rolls(map: &Map) -> usize {
         .filter(|p| *map.meta(*p) < 4)
         .count()
 }
-
-fn remove_rolls(map: &mut Map) -> Option<usize> {
-    count_adj_rolls(map);
-
-    let removable: Vec<Position> = map
-        .find_all('@')
-        .into_iter()
-        .filter(|p| *map.meta(*p) < 4)
-        .collect();
-
-    for pos in &removable {
-        map.set(*pos, '.');
-    }
-
-    if removable.len() > 0 {
-        return Some(removable.len());
-    }
-    return None;
-}
-
-#[test]
-fn test_part1() {
-    let mut map = MetaGrid::from_str(
-        "..@@.@@@@.
-@@@.@.@.@@
-@@@@@.@.@@
-@.@@@@..@.
-@@.@@@@.@@
-.@@@@@@@.@
-.@.@.@.@@@
-@.@@@.@@@@
-.@@@@@@@@.
-@.@.@@@.@.",
-    )
-    .expect("should not error");
-
-    count_adj_rolls(&mut map);
-
-    assert_eq!(13, n_accessible_rolls(&map));
-}
-
-#[test]
-fn test_part2() {
-    let mut map = MetaGrid::from_str(
-        "..@@.@@@@.
-@@@.@.@.@@
-@@@@@.@.@@
-@.@@@@..@.
-@@.@@@@.@@
-.@@@@@@@.@
-.@.@.@.@@@
-@.@@@.@@@@
-.@@@@@@@@.
-@.@.@@@.@.",
-    )
-    .expect("should not error");
-
-    let mut total_removed = 0;
-    while let Some(n_removed) = remove_rolls(&mut map) {
-        total_removed += n_removed;
-    }
-
-    assert_eq!(43, total_removed);
-}
@@ -8,16 +8,12 @@ use crate::shared::*;
 pub struct Day05;
 
 impl Solution for Day05 {
-    fn part1(&self) -> Result<String> {
-        let input = input(5)?;
-
-        return Ok(part1(&input)?.to_string());
+    fn part1(&self, input: &str) -> Result<String> {
+        return Ok(part1(input)?.to_string());
     }
 
-    fn part2(&self) -> Result<String> {
-        let input = input(5)?;
-
-        return Ok(part2(&input)?.to_string());
+    fn part2(&self, input: &str) -> Result<String> {
+        return Ok(part2(input)?.to_string());
     }
 }
 
@@ -25,26 +21,26 @@ fn part1(input: &str) -> Result<usize> {
     let mut ranges: Vec<RangeInclusive<u64>> = Vec::new();
 
     enum Mode {
-        RANGES,
-        INGREDIENTS,
+        Ranges,
+        Ingredients,
     }
-    let mut mode = Mode::RANGES;
+    let mut mode = Mode::Ranges;
     let mut fresh = 0;
     'lines: for line in input.lines() {
-        if line == "" {
-            mode = Mode::INGREDIENTS;
+        if line.is_empty() {
+            mode = Mode::Ingredients;
             continue;
         }
 
         match mode {
-            Mode::RANGES => {
+            Mode::Ranges => {
                 let (l, r) = match line.split_once("-") {
                     Some((l, r)) => (l, r),
                     None => return err(&format!("invalid range '{line}'")),
                 };
                 ranges.push(RangeInclusive::new(l.parse()?, r.parse()?));
             }
-            Mode::INGREDIENTS => {
+            Mode::Ingredients => {
                 let id: u64 = line.parse()?;
 
                 for range in &ranges {
@@ -60,30 +56,11 @@ fn part1(input: &str) -> Result<usize> {
     return Ok(fresh);
 }
 
-#[test]
-fn test_part1() {
-    let fresh = part1(
-        "3-5
-10-14
-16-20
-12-18
-
-1
-5
-8
-11
-17
-32",
-    );
-
-    assert_eq!(3, fresh.unwrap());
-}
-
 fn part2(input: &str) -> Result<u64> {
     let mut set = MergingRangeInclusiveSet::new();
 
     for line in input.lines() {
-        if line == "" {
+        if line.is_empty() {
             break;
         }
 
@@ -96,25 +73,6 @@ fn part2(input: &str) -> Result<u64> {
     return Ok(set.sum());
 }
 
-#[test]
-fn test_part2() {
-    let total_ids = part2(
-        "3-5
-10-14
-16-20
-12-18
-
-1
-5
-8
-11
-17
-32",
-    );
-
-    assert_eq!(14, total_ids.unwrap());
-}
-
 struct MergingRangeInclusiveSet {
     vals: Vec<u64>,
 }
@@ -147,12 +105,11 @@ impl MergingRangeInclusiveSet {
                     return;
                 }
             } else {
-                if idx != self.vals.len() - 1 {
-                    if start > self.vals[idx] && end < self.vals[idx + 1] {
-                        self.vals.insert(idx + 1, start);
-                        self.vals.insert(idx + 2, end);
-                        return;
-                    }
+                if idx != self.vals.len() - 1 && start > self.vals[idx] && end < self.vals[idx + 1]
+                {
+                    self.vals.insert(idx + 1, start);
+                    self.vals.insert(idx + 2, end);
+                    return;
                 }
             }
         }
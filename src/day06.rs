@@ -3,16 +3,12 @@ use crate::shared::*;
 pub struct Day06;
 
 impl Solution for Day06 {
-    fn part1(&self) -> Result<String> {
-        let input = input(6)?;
-
-        Ok(part1(&input)?.to_string())
+    fn part1(&self, input: &str) -> Result<String> {
+        Ok(part1(input)?.to_string())
     }
 
-    fn part2(&self) -> Result<String> {
-        let input = input(6)?;
-
-        Ok(part2(&input)?.to_string())
+    fn part2(&self, input: &str) -> Result<String> {
+        Ok(part2(input)?.to_string())
     }
 }
 
@@ -48,23 +44,14 @@ fn part1(input: &str) -> Result<u64> {
     return Ok(sum);
 }
 
-#[test]
-fn test_part1() {
-    let result = part1(
-        " 123 328  51 64 
- 45 64  387 23 
-  6 98  215 314
-*   +   *   +
-",
-    );
-
-    assert_eq!(4277556, result.unwrap());
-}
-
-const SPACE: u8 = ' ' as u8;
+const SPACE: u8 = b' ';
 
 fn part2(input: &str) -> Result<u64> {
-    let cg = CharGrid::from_str(input)?;
+    // Columns line up by position, so lines whose trailing spaces have been stripped are padded
+    // back out.
+    let cg: ByteGrid = GridParser::new(Encoding::Bytes)
+        .pad(' ')
+        .parse(input, |c| c as u8)?;
 
     let mut this_sum = 0;
     let mut this_product = 1;
@@ -75,14 +62,14 @@ fn part2(input: &str) -> Result<u64> {
         let mut this_operand: u64 = 0;
 
         for y in 0..=cg.y_max() - 1 {
-            let c = cg.at(x, y) as u8;
+            let c = cg.at_xy(x, y);
             match c {
                 0b0011_0000..=0b0011_1001 => {
                     this_operand *= 10;
                     this_operand += (c & 0b0100_1111) as u64;
                 }
                 SPACE => {}
-                _ => return err(&format!("unexpected operand character '{}'", cg.at(x, y))),
+                _ => return err(&format!("unexpected operand character '{}'", c as char)),
             }
         }
 
@@ -91,14 +78,14 @@ fn part2(input: &str) -> Result<u64> {
             this_product *= this_operand;
         }
 
-        match cg.at(x, cg.y_max()) {
-            ' ' => {}
-            '+' => {
+        match cg.at_xy(x, cg.y_max()) {
+            SPACE => {}
+            b'+' => {
                 sum += this_sum;
                 this_sum = 0;
                 this_product = 1;
             }
-            '*' => {
+            b'*' => {
                 sum += this_product;
                 this_sum = 0;
                 this_product = 1;
@@ -106,7 +93,7 @@ fn part2(input: &str) -> Result<u64> {
             _ => {
                 return err(&format!(
                     "unexpected operator character '{}'",
-                    cg.at(x, cg.y_max())
+                    cg.at_xy(x, cg.y_max()) as char
                 ));
             }
         }
@@ -116,13 +103,9 @@ fn part2(input: &str) -> Result<u64> {
 }
 
 #[test]
-fn test_part2() {
-    let result = part2(concat!(
-        "123 328  51 64 \n",
-        " 45 64  387 23 \n",
-        "  6 98  215 314\n",
-        "*   +   *   +  \n",
-    ));
-
-    assert_eq!(3263827, result.unwrap());
+fn test_part2_stripped() {
+    let input = crate::examples::load(6, "example").unwrap();
+    let stripped: Vec<&str> = input.lines().map(str::trim_end).collect();
+
+    assert_eq!(3263827, part2(&stripped.join("\n")).unwrap());
 }
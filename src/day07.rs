@@ -3,16 +3,12 @@ use crate::shared::*;
 pub struct Day07;
 
 impl Solution for Day07 {
-    fn part1(&self) -> Result<String> {
-        let input = input(7)?;
-
-        Ok(part1(&input)?.to_string())
+    fn part1(&self, input: &str) -> Result<String> {
+        Ok(part1(input)?.to_string())
     }
 
-    fn part2(&self) -> Result<String> {
-        let input = input(7)?;
-
-        Ok(part2(&input)?.to_string())
+    fn part2(&self, input: &str) -> Result<String> {
+        Ok(part2(input)?.to_string())
     }
 }
 
@@ -23,48 +19,27 @@ fn part1(input: &str) -> Result<usize> {
         for x in 0..mg.width() {
             let here = Position::at(x, y);
 
-            if let Some((c, _)) = mg.at_checked(here + UP) {
-                if c == 'S' || c == '|' {
-                    match mg.at(here) {
-                        ('.', _) => mg.set(here, '|'),
-                        ('^', _) => {
-                            mg.set_checked(here + LEFT, '|');
-                            mg.set_checked(here + RIGHT, '|');
-                            mg.set_meta(here, 1);
-                        }
-                        ('|', _) => {}
-                        (c, _) => return err(&format!("unexpected character in part1 walk '{c}'")),
+            if let Some((c, _)) = mg.at_checked(here + UP)
+                && (c == 'S' || c == '|')
+            {
+                match mg.at(here) {
+                    ('.', _) => mg.set(here, '|'),
+                    ('^', _) => {
+                        mg.set_checked(here + LEFT, '|');
+                        mg.set_checked(here + RIGHT, '|');
+                        mg.set_meta(here, 1);
                     }
+                    ('|', _) => {}
+                    (c, _) => return err(&format!("unexpected character in part1 walk '{c}'")),
                 }
             }
         }
+        visualize::record_frame(&mg);
     }
 
     return Ok(mg.sum_meta());
 }
 
-#[test]
-fn test_part1() {
-    let input = ".......S.......
-...............
-.......^.......
-...............
-......^.^......
-...............
-.....^.^.^.....
-...............
-....^.^...^....
-...............
-...^.^...^.^...
-...............
-..^...^.....^..
-...............
-.^.^.^.^.^...^.
-...............";
-
-    assert_eq!(21, part1(input).unwrap());
-}
-
 fn part2(input: &str) -> Result<usize> {
     let mut mg: MetaGrid<usize> = MetaGrid::from_str(input)?;
 
@@ -96,25 +71,3 @@ fn part2(input: &str) -> Result<usize> {
 
     return Ok(total);
 }
-
-#[test]
-fn test_part2() {
-    let input = ".......S.......
-...............
-.......^.......
-...............
-......^.^......
-...............
-.....^.^.^.....
-...............
-....^.^...^....
-...............
-...^.^...^.^...
-...............
-..^...^.....^..
-...............
-.^.^.^.^.^...^.
-...............";
-
-    assert_eq!(40, part2(input).unwrap(),);
-}
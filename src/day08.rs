@@ -1,23 +1,16 @@
-use std::{
-    cmp::{max, min},
-    collections::{BinaryHeap, HashMap, HashSet},
-};
+use std::collections::BinaryHeap;
 
 use crate::shared::*;
 
 pub struct Day08;
 
 impl Solution for Day08 {
-    fn part1(&self) -> Result<String> {
-        let input = input(8)?;
-
-        return part1(&input, 1000, 3).map(|v| v.to_string());
+    fn part1(&self, input: &str) -> Result<String> {
+        return part1(input, 1000, 3).map(|v| v.to_string());
     }
 
-    fn part2(&self) -> Result<String> {
-        let input = input(8)?;
-
-        return part2(&input).map(|v| v.to_string());
+    fn part2(&self, input: &str) -> Result<String> {
+        return part2(input).map(|v| v.to_string());
     }
 }
 
@@ -27,8 +20,7 @@ struct Distance(f32);
 impl Eq for Distance {}
 impl PartialOrd for Distance {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        // reverse sort order for distance in binary heap
-        other.0.partial_cmp(&self.0)
+        Some(self.cmp(other))
     }
 }
 impl Ord for Distance {
@@ -51,30 +43,33 @@ struct JunctionBox {
     x: f32,
     y: f32,
     z: f32,
-    circuit_id: usize,
 }
 
 #[derive(Debug)]
 struct Space {
     nodes: Vec<JunctionBox>,
+    // Which boxes are connected into the same circuit.
+    circuits: UnionFind,
 }
 
 impl Space {
     fn from_str(input: &str) -> Result<Self> {
-        let mut space = Self { nodes: Vec::new() };
+        let mut space = Self {
+            nodes: Vec::new(),
+            circuits: UnionFind::default(),
+        };
 
         for line in input.lines() {
             let values: Vec<_> = line.split(",").collect();
             if values.len() != 3 {
                 return err(&format!("malformed line: {line}"));
             }
-            let next_id = space.nodes.len();
             space.nodes.push(JunctionBox {
                 x: values[0].parse()?,
                 y: values[1].parse()?,
                 z: values[2].parse()?,
-                circuit_id: next_id,
             });
+            space.circuits.add();
         }
 
         return Ok(space);
@@ -83,7 +78,7 @@ impl Space {
     fn closest_boxes(&self) -> BinaryHeap<(Distance, (usize, usize))> {
         let mut heap: BinaryHeap<(Distance, (usize, usize))> = BinaryHeap::new();
 
-        let ids: Vec<usize> = (0..self.nodes.len()).into_iter().collect();
+        let ids: Vec<usize> = (0..self.nodes.len()).collect();
         for ids in combinations(2, &ids) {
             let a = &self.nodes[ids[0]];
             let b = &self.nodes[ids[1]];
@@ -103,59 +98,11 @@ impl Space {
 
         for _ in 0..n {
             let (_, (a, b)) = heap.pop().unwrap();
-            if !self.is_connected(a, b) {
-                self.connect(a, b);
-            }
+            self.circuits.union(a, b);
         }
 
         return Ok(());
     }
-
-    fn circuits(&self) -> Vec<HashSet<usize>> {
-        let mut circuits: HashMap<usize, HashSet<usize>> = HashMap::new();
-
-        for (id, node) in self.nodes.iter().enumerate() {
-            circuits
-                .entry(node.circuit_id)
-                .and_modify(|hs| {
-                    hs.insert(id);
-                })
-                .or_insert_with(|| {
-                    let mut hs = HashSet::new();
-                    hs.insert(id);
-                    return hs;
-                });
-        }
-
-        return circuits.into_iter().map(|(k, v)| v).collect();
-    }
-
-    fn is_connected(&self, a: usize, b: usize) -> bool {
-        self.nodes[a].circuit_id == self.nodes[b].circuit_id
-    }
-
-    fn connect(&mut self, a: usize, b: usize) {
-        let source_id = max(self.nodes[a].circuit_id, self.nodes[b].circuit_id);
-        let target_id = min(self.nodes[a].circuit_id, self.nodes[b].circuit_id);
-
-        for node in &mut self.nodes {
-            if node.circuit_id == source_id {
-                node.circuit_id = target_id;
-            }
-        }
-    }
-
-    fn all_connected(&self) -> bool {
-        let target = self.nodes[0].circuit_id;
-
-        for node in &self.nodes {
-            if node.circuit_id != target {
-                return false;
-            }
-        }
-
-        return true;
-    }
 }
 
 fn part1(input: &str, conns: usize, top: usize) -> Result<usize> {
@@ -163,90 +110,29 @@ fn part1(input: &str, conns: usize, top: usize) -> Result<usize> {
 
     space.connect_closest(conns)?;
 
-    let mut circuits = space.circuits();
-    circuits.sort_by_key(HashSet::len);
-    let top: Vec<_> = circuits.into_iter().rev().take(top).collect();
+    let mut sizes: Vec<usize> = space.circuits.groups().iter().map(Vec::len).collect();
+    sizes.sort();
 
-    Ok(top
-        .iter()
-        .map(HashSet::len)
-        .reduce(std::ops::Mul::mul)
-        .unwrap())
+    Ok(sizes.iter().rev().take(top).product())
 }
 
 #[test]
 fn test_part1() {
-    let input = "162,817,812
-57,618,57
-906,360,560
-592,479,940
-352,342,300
-466,668,158
-542,29,236
-431,825,988
-739,650,466
-52,470,668
-216,146,977
-819,987,18
-117,168,530
-805,96,715
-346,949,466
-970,615,88
-941,993,340
-862,61,35
-984,92,344
-425,690,689";
-
-    assert_eq!(40, part1(input, 10, 3).unwrap());
+    let input = crate::examples::load(8, "example").unwrap();
+
+    assert_eq!(40, part1(&input, 10, 3).unwrap());
 }
 
 fn part2(input: &str) -> Result<usize> {
     let mut space = Space::from_str(input)?;
 
     let mut closest = space.closest_boxes();
-    loop {
-        if let Some((d, (a, b))) = closest.pop() {
-            println!(
-                "connecting {a}(x={}, circuit={}) to {b}(x={}, circuit={}) at a distance of {}",
-                space.nodes[a].x,
-                space.nodes[a].circuit_id,
-                space.nodes[b].x,
-                space.nodes[b].circuit_id,
-                d.0
-            );
-            space.connect(a, b);
-            if space.all_connected() {
-                dbg!(closest.pop());
-                return Ok((space.nodes[a].x as usize * space.nodes[b].x as usize) as usize);
-            }
-        } else {
-            return err("ran out of boxes to connect");
+    while let Some((_, (a, b))) = closest.pop() {
+        // The last connection needed is the one that leaves a single circuit.
+        if space.circuits.union(a, b) && space.circuits.sets() == 1 {
+            return Ok(space.nodes[a].x as usize * space.nodes[b].x as usize);
         }
     }
-}
 
-#[test]
-fn test_part2() {
-    let input = "162,817,812
-57,618,57
-906,360,560
-592,479,940
-352,342,300
-466,668,158
-542,29,236
-431,825,988
-739,650,466
-52,470,668
-216,146,977
-819,987,18
-117,168,530
-805,96,715
-346,949,466
-970,615,88
-941,993,340
-862,61,35
-984,92,344
-425,690,689";
-
-    assert_eq!(25272, part2(input).unwrap());
+    return err("ran out of boxes to connect");
 }
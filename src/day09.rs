@@ -3,11 +3,11 @@ use crate::shared::*;
 pub struct Day09;
 
 impl Solution for Day09 {
-    fn part1(&self) -> Result<String> {
+    fn part1(&self, _input: &str) -> Result<String> {
         todo!("not implemented");
     }
 
-    fn part2(&self) -> Result<String> {
+    fn part2(&self, _input: &str) -> Result<String> {
         todo!("not implemented");
     }
 }
@@ -0,0 +1,65 @@
+use crate::answers::Answers;
+use crate::shared::*;
+use std::fs::{read_dir, read_to_string};
+use std::path::PathBuf;
+
+// Puzzle examples live in examples/{day}/{name}.txt, with the expected answers for each part in
+// examples/{day}/{name}.toml, in the same format as answers/{day}.toml. An example may leave out
+// a part whose answer does not apply, e.g. when the puzzle uses different parameters for it.
+
+pub fn example_path(day: u32, name: &str) -> PathBuf {
+    PathBuf::from("examples")
+        .join(day.to_string())
+        .join(format!("{name}.txt"))
+}
+
+// Reads an example, trimmed the same way as puzzle input.
+pub fn load(day: u32, name: &str) -> Result<String> {
+    let path = example_path(day, name);
+    if !path.exists() {
+        let names = names(day)?;
+        return err(&format!(
+            "day {day} has no example {name} (examples: {})",
+            if names.is_empty() {
+                "none".to_owned()
+            } else {
+                names.join(", ")
+            }
+        ));
+    }
+
+    match read_to_string(&path) {
+        Ok(v) => Ok(v.trim_end_matches("\n").to_owned()),
+        Err(e) => err(&format!("failed to read file {}: {e}", path.display())),
+    }
+}
+
+pub fn expected(day: u32, name: &str) -> Result<Answers> {
+    let path = example_path(day, name).with_extension("toml");
+
+    match read_to_string(&path) {
+        Ok(s) => Answers::from_str(&s).map_err(|e| Error::from(format!("{}: {e}", path.display()))),
+        Err(e) => err(&format!("failed to read file {}: {e}", path.display())),
+    }
+}
+
+// The names of every example for a day, sorted.
+pub fn names(day: u32) -> Result<Vec<String>> {
+    let dir = PathBuf::from("examples").join(day.to_string());
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    for entry in read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt")
+            && let Some(name) = path.file_stem()
+        {
+            names.push(name.to_string_lossy().into_owned());
+        }
+    }
+    names.sort();
+
+    Ok(names)
+}
@@ -1,60 +1,396 @@
+#![allow(clippy::needless_return)]
+
+mod answers;
+mod examples;
+mod registry;
+mod runner;
+mod scaffold;
 mod shared;
+mod tui;
 
 use shared::*;
-use std::collections::HashMap;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
+use std::io::{BufRead, IsTerminal, Write};
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+registry::days! {
+    1 => day01::Day01 { title: "Secret Entrance", parts: [1, 2] },
+    2 => day02::Day02 { title: "Gift Shop", parts: [1, 2] },
+    3 => day03::Day03 { title: "Lobby", parts: [1, 2] },
+    4 => day04::Day04 { title: "Printing Department", parts: [1, 2] },
+    5 => day05::Day05 { title: "Cafeteria", parts: [1, 2] },
+    6 => day06::Day06 { title: "Trash Compactor", parts: [1, 2] },
+    7 => day07::Day07 { title: "Laboratories", parts: [1, 2] },
+    8 => day08::Day08 { title: "Playground", parts: [1, 2] },
+    9 => day09::Day09 { title: "Movie Theater", parts: [] },
+    10 => day10::Day10 { title: "Factory", parts: [] },
+    11 => day11::Day11 { title: "Reactor", parts: [] },
+    12 => day12::Day12 { title: "Christmas Tree Farm", parts: [] },
+}
+
+const USAGE: &str = "usage: aoc2025 <day#> <part#> [options]
+       aoc2025 <days> [options]    (all, 4, 3..8, 3..=8, 9..)
+       aoc2025 verify [<days>]
+       aoc2025 new <day#> [<title>]
+       aoc2025 tui
+
+options:
+  --input <path>|-      read a single day's input from a file, or - for stdin
+  --example <name>      read input from examples/<day>/<name>.txt
+  --bench N             run a single part N times and report timings
+  --format text|json    print results as text, or one JSON object per run or benchmark
+  --visualize           step through the grids a single part records as it runs
+  --export <path>       save those grids as a .gif animation, or the last as a .png or .ppm
+  --scale N             size in pixels of each cell of an exported grid (default 4)
+
+Input is read from inputs/<day>.txt, or from $AOC_INPUT_DIR/<day>.txt if set.";
+
+#[derive(Debug, Default, PartialEq)]
+enum Format {
+    #[default]
+    Text,
+    // One JSON object per run, one per line.
+    Json,
+}
+
+// Command line arguments: positional arguments in order, plus any flags.
+#[derive(Debug, Default)]
+struct Args {
+    positional: Vec<String>,
+    bench: Option<u32>,
+    input: Option<InputSource>,
+    example: Option<String>,
+    format: Format,
+    visualize: bool,
+    export: Option<PathBuf>,
+    scale: Option<usize>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Args> {
+        let mut parsed = Args::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--bench" => match args.next() {
+                    Some(n) => parsed.bench = Some(n.parse()?),
+                    None => return err("--bench requires a number of runs"),
+                },
+                "--input" => match args.next() {
+                    Some(path) => parsed.input = Some(InputSource::parse(&path)),
+                    None => return err("--input requires a path, or - for stdin"),
+                },
+                "--example" => match args.next() {
+                    Some(name) => parsed.example = Some(name),
+                    None => return err("--example requires the name of an example"),
+                },
+                "--format" => match args.next().as_deref() {
+                    Some("text") => parsed.format = Format::Text,
+                    Some("json") => parsed.format = Format::Json,
+                    _ => return err("--format requires text or json"),
+                },
+                "--visualize" => parsed.visualize = true,
+                "--export" => match args.next() {
+                    Some(path) => parsed.export = Some(PathBuf::from(path)),
+                    None => return err("--export requires a path"),
+                },
+                "--scale" => match args.next().map(|n| n.parse::<usize>()) {
+                    Some(Ok(0)) => return err("--scale must be at least 1 pixel"),
+                    Some(n) => parsed.scale = Some(n?),
+                    None => return err("--scale requires a number of pixels"),
+                },
+                flag if flag.starts_with("--") => return err(&format!("unknown flag {flag}")),
+                _ => parsed.positional.push(arg),
+            }
+        }
+
+        if parsed.input.is_some() && parsed.example.is_some() {
+            return err("--input and --example cannot be used together");
+        }
+
+        Ok(parsed)
+    }
+
+    // Whether the command runs a single day, the only case where one --input file makes sense.
+    fn selects_one_day(&self) -> bool {
+        let positional: Vec<&str> = self.positional.iter().map(String::as_str).collect();
+        match positional.as_slice() {
+            ["verify", ..] | ["tui"] | ["new", ..] => false,
+            [_day, _part] => true,
+            [selection] => {
+                runner::parse_days(selection).is_ok_and(|days| days.start() == days.end())
+            }
+            _ => false,
+        }
+    }
+
+    // Whether the command runs a single part once, the only case where there are grids to
+    // visualize or export.
+    fn selects_one_part(&self) -> bool {
+        let positional: Vec<&str> = self.positional.iter().map(String::as_str).collect();
+        match positional.as_slice() {
+            ["verify", ..] | ["new", ..] => false,
+            [_day, _part] => self.bench.is_none(),
+            _ => false,
+        }
+    }
+
+    fn which_input(&self) -> runner::Input {
+        match &self.example {
+            Some(name) => runner::Input::Example(name.clone()),
+            None => runner::Input::Puzzle,
+        }
+    }
+}
 
 fn main() -> Result<()> {
-    let args: Vec<_> = std::env::args().collect();
-    if args.len() != 3 {
-        return Err(Error::new("usage: aoc2025 <day#> <part#>"));
-    }
-
-    let day = args[1].parse::<u32>()?;
-    let part = args[2].parse::<u32>()?;
-
-    let mut days: HashMap<u32, Box<dyn Solution>> = HashMap::new();
-    days.insert(01, Box::new(day01::Day01));
-    days.insert(02, Box::new(day02::Day02));
-    days.insert(03, Box::new(day03::Day03));
-    days.insert(04, Box::new(day04::Day04));
-    days.insert(05, Box::new(day05::Day05));
-    days.insert(06, Box::new(day06::Day06));
-    days.insert(07, Box::new(day07::Day07));
-    days.insert(08, Box::new(day08::Day08));
-    days.insert(09, Box::new(day09::Day09));
-    days.insert(10, Box::new(day10::Day10));
-    days.insert(11, Box::new(day11::Day11));
-    days.insert(12, Box::new(day12::Day12));
-
-    if let Some(solution) = days.get(&day) {
-        let result = if part == 1 {
-            solution.part1()
-        } else if part == 2 {
-            solution.part2()
-        } else {
-            return Err(Error::new("invalid part number"));
-        };
-
-        match result {
-            Ok(v) => println!("result: {}", v),
-            Err(e) => println!("error: {}", e),
-        }
-    } else {
-        return Err(Error::new(&format!("day {day} not found")));
+    let args = Args::parse(std::env::args().skip(1))?;
+    let days = registry();
+
+    if args.input.is_some() && !args.selects_one_day() {
+        return err("--input is read by every day run, so it can only be used with a single day");
+    }
+    if (args.visualize || args.export.is_some()) && !args.selects_one_part() {
+        return err("--visualize and --export need a single day and part, without --bench");
+    }
+    override_input(args.input.clone());
+    let which = args.which_input();
+
+    let positional: Vec<&str> = args.positional.iter().map(String::as_str).collect();
+
+    match (positional.as_slice(), args.bench) {
+        (["verify"], None) => verify(&days, 1..=u32::MAX, &args.format)?,
+        (["verify", selection], None) => {
+            verify(&days, runner::parse_days(selection)?, &args.format)?
+        }
+        (["tui"], None) => tui::run(&days)?,
+        (["new", day], None) => new_day(day.parse()?, "")?,
+        (["new", day, title], None) => new_day(day.parse()?, title)?,
+        ([selection], None) => {
+            let runs = runner::run_all(&days, runner::parse_days(selection)?, &which);
+
+            match args.format {
+                Format::Text => print!("{}", runner::draw_table(&days, &runs)),
+                Format::Json => runs.iter().for_each(|r| println!("{}", r.to_json())),
+            }
+
+            let failed = runs.iter().filter(|r| r.failed()).count();
+            if failed > 0 {
+                return err(&format!("{failed} run(s) failed"));
+            }
+        }
+        ([day, part], bench) => {
+            let day = day.parse::<u32>()?;
+            let part = part.parse::<u32>()?;
+
+            let Some(solution) = days.get(&day).map(|d| d.solution.as_ref()) else {
+                return Err(Error::new(&format!("day {day} not found")));
+            };
+
+            if let Some(n) = bench {
+                let bench = runner::bench(solution, day, part, &which, n)?;
+                match args.format {
+                    Format::Text => print!("{}", bench.draw()),
+                    Format::Json => println!("{}", bench.to_json()),
+                }
+                return Ok(());
+            }
+
+            if args.visualize || args.export.is_some() {
+                visualize::start_recording();
+            }
+            let run = runner::load_and_run(solution, day, part, &which);
+            let frames = visualize::stop_recording();
+            if args.format == Format::Json {
+                println!("{}", run.to_json());
+            }
+
+            match (&run.answer, &args.format) {
+                (Some(v), Format::Text) => {
+                    println!("result: {}", v);
+                    match &args.example {
+                        Some(name) => check_example(day, part, name, v)?,
+                        None if args.input.is_none() => check_answer(day, part, v)?,
+                        None => {}
+                    }
+                }
+                (Some(_), Format::Json) => {}
+                (None, _) => return Err(Error::from(run.error().unwrap_or_default())),
+            }
+
+            if let Some(path) = &args.export {
+                let scale = args.scale.unwrap_or(4);
+                image::export(path, &frames, &image::Palette::new(), scale)?;
+                println!("wrote {}", path.display());
+            }
+            if args.visualize {
+                visualize::visualize(&frames)?;
+            }
+        }
+        _ => return Err(Error::new(USAGE)),
+    }
+
+    Ok(())
+}
+
+fn new_day(day: u32, title: &str) -> Result<()> {
+    for path in scaffold::new_day(day, title)? {
+        println!("wrote {}", path.display());
     }
 
     Ok(())
 }
+
+// Runs every selected day and compares each answer with the one recorded in answers/, failing if
+// any do not match.
+fn verify(
+    days: &registry::Registry,
+    selection: RangeInclusive<u32>,
+    format: &Format,
+) -> Result<()> {
+    let mut checked = Vec::new();
+
+    for run in runner::run_all(days, selection, &runner::Input::Puzzle) {
+        let answers = answers::load(run.day)?;
+        let verdict = answers::check(&run, &answers);
+        let expected = answers.get(run.part).map(str::to_owned);
+        checked.push((run, expected, verdict));
+    }
+
+    match format {
+        Format::Text => print!("{}", answers::draw_verdicts(&checked)),
+        Format::Json => {
+            for (run, expected, verdict) in &checked {
+                println!(
+                    "{}",
+                    answers::verdict_json(run, expected.as_deref(), verdict)
+                );
+            }
+        }
+    }
+
+    let failures = checked
+        .iter()
+        .filter(|(_, _, v)| *v == answers::Verdict::Fail)
+        .count();
+    if failures > 0 {
+        return err(&format!("{failures} run(s) failed or did not match"));
+    }
+
+    Ok(())
+}
+
+// Compares a fresh answer with the recorded one. If none has been recorded yet and we are running
+// interactively, offers to record it once it has been accepted.
+fn check_answer(day: u32, part: u32, answer: &str) -> Result<()> {
+    let answers = answers::load(day)?;
+
+    match answers.get(part) {
+        Some(expected) if expected == answer => println!("matches recorded answer"),
+        Some(expected) => println!("does NOT match recorded answer {expected}"),
+        None if std::io::stdin().is_terminal() && std::io::stdout().is_terminal() => {
+            print!("was {answer} accepted? record it in answers/{day}.toml [y/N] ");
+            std::io::stdout().flush()?;
+
+            let mut reply = String::new();
+            std::io::stdin().lock().read_line(&mut reply)?;
+            if reply.trim().eq_ignore_ascii_case("y") {
+                answers::record(day, part, answer)?;
+            }
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+fn check_example(day: u32, part: u32, name: &str, answer: &str) -> Result<()> {
+    match examples::expected(day, name)?.get(part) {
+        Some(expected) if expected == answer => println!("matches example answer"),
+        Some(expected) => println!("does NOT match example answer {expected}"),
+        None => println!("example {name} has no answer for part {part}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_input_needs_one_day() {
+    let parse = |args: &str| Args::parse(args.split(' ').map(str::to_owned)).unwrap();
+
+    assert!(parse("4 --input x.txt").selects_one_day());
+    assert!(parse("4 2 --input x.txt").selects_one_day());
+    assert!(parse("3..=3").selects_one_day());
+    assert!(!parse("all --input x.txt").selects_one_day());
+    assert!(!parse("3..8").selects_one_day());
+    assert!(!parse("verify 4").selects_one_day());
+    assert!(!parse("tui").selects_one_day());
+}
+
+#[test]
+fn test_export_needs_one_part() {
+    let parse = |args: &str| Args::parse(args.split(' ').map(str::to_owned)).unwrap();
+
+    assert!(parse("4 2 --export x.gif").selects_one_part());
+    assert!(!parse("4 2 --bench 3 --export x.gif").selects_one_part());
+    assert!(!parse("all --export x.gif").selects_one_part());
+    assert!(!parse("4 --visualize").selects_one_part());
+    assert!(!parse("verify 4 --visualize").selects_one_part());
+    assert!(!parse("new 4 --visualize").selects_one_part());
+}
+
+#[test]
+fn test_scale() {
+    let parse = |args: &str| Args::parse(args.split(' ').map(str::to_owned));
+
+    assert_eq!(Some(2), parse("4 2 --scale 2").unwrap().scale);
+    assert!(parse("4 2 --scale 0").is_err());
+    assert!(parse("4 2 --scale x").is_err());
+}
+
+#[test]
+fn test_examples() {
+    let mut failures = Vec::new();
+
+    for (day, entry) in registry() {
+        for name in examples::names(day).unwrap() {
+            let input = examples::load(day, &name).unwrap();
+            let expected = examples::expected(day, &name).unwrap();
+
+            for part in [1, 2] {
+                let Some(expected) = expected.get(part) else {
+                    continue;
+                };
+
+                let run = runner::run(entry.solution.as_ref(), day, part, &input);
+                if run.answer.as_deref() != Some(expected) {
+                    failures.push(format!(
+                        "day {day} part {part} example {name}: expected {expected}, got {}",
+                        run.answer.clone().unwrap_or_else(|| runner::describe(&run))
+                    ));
+                }
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_registry_parts() {
+    // Parts not marked as solved should still be todo!(), and solved parts should not be. Running
+    // solved parts on empty input only needs to get past that distinction.
+    for (day, entry) in registry() {
+        assert_eq!(day, entry.number);
+
+        for part in [1, 2] {
+            let run = runner::quietly(|| runner::run(entry.solution.as_ref(), day, part, ""));
+            assert_eq!(
+                !entry.implemented(part),
+                run.status == runner::Status::NotImplemented,
+                "day {day} part {part} is marked as {}implemented",
+                if entry.implemented(part) { "" } else { "not " }
+            );
+        }
+    }
+}
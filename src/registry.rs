@@ -0,0 +1,51 @@
+use crate::shared::*;
+use std::collections::BTreeMap;
+
+// A day registered with days!, along with its metadata.
+pub struct Day {
+    pub number: u32,
+    pub title: &'static str,
+    // The parts which have been solved. Running any other part will hit a todo!().
+    pub parts: &'static [u32],
+    pub solution: Box<dyn Solution>,
+}
+
+impl Day {
+    pub fn implemented(&self, part: u32) -> bool {
+        self.parts.contains(&part)
+    }
+}
+
+pub type Registry = BTreeMap<u32, Day>;
+
+// Declares the module for each day and defines registry(), which builds the table of days. Each
+// entry gives the day's number, its module and Solution, its title and its solved parts:
+//
+//     days! {
+//         1 => day01::Day01 { title: "Secret Entrance", parts: [1, 2] },
+//     }
+macro_rules! days {
+    ($(
+        $number:literal => $module:ident::$solution:ident {
+            title: $title:literal,
+            parts: [$($part:literal),*] $(,)?
+        }
+    ),* $(,)?) => {
+        $(mod $module;)*
+
+        pub fn registry() -> $crate::registry::Registry {
+            let mut days = $crate::registry::Registry::new();
+            $(
+                days.insert($number, $crate::registry::Day {
+                    number: $number,
+                    title: $title,
+                    parts: &[$($part),*],
+                    solution: Box::new($module::$solution),
+                });
+            )*
+            days
+        }
+    };
+}
+
+pub(crate) use days;
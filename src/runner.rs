@@ -0,0 +1,579 @@
+use crate::examples;
+use crate::registry::Registry;
+use crate::shared::*;
+use std::cell::Cell;
+use std::ops::RangeInclusive;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Ok,
+    Error(String),
+    NotImplemented,
+    Panicked(String),
+    // Not run, for the reason given.
+    Skipped(String),
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Ok => f.write_str("ok"),
+            Status::Error(_) => f.write_str("error"),
+            Status::NotImplemented => f.write_str("not implemented"),
+            Status::Panicked(_) => f.write_str("panicked"),
+            Status::Skipped(_) => f.write_str("skipped"),
+        }
+    }
+}
+
+// The outcome of running a single part of a single day.
+#[derive(Debug)]
+pub struct Run {
+    pub day: u32,
+    pub part: u32,
+    pub answer: Option<String>,
+    pub status: Status,
+    // Time spent solving, not including loading the input.
+    pub elapsed: Duration,
+    // Time spent loading the input.
+    pub load: Duration,
+    // A hash of the input the run was given, if it could be loaded.
+    pub input_hash: Option<u64>,
+}
+
+impl Run {
+    pub fn failed(&self) -> bool {
+        matches!(self.status, Status::Error(_) | Status::Panicked(_))
+    }
+
+    // Why the run did not produce an answer, if it did not.
+    pub fn error(&self) -> Option<String> {
+        match &self.status {
+            Status::Ok => None,
+            Status::Error(msg) => Some(msg.clone()),
+            _ => Some(describe(self)),
+        }
+    }
+
+    // Encodes the run as a single line JSON object.
+    pub fn to_json(&self) -> String {
+        let string = |s: Option<&str>| s.map(json_string).unwrap_or_else(|| "null".to_owned());
+        let error = self.error();
+
+        format!(
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"error\":{},\"duration_ns\":{},\"load_ns\":{},\"input_hash\":{}}}",
+            self.day,
+            self.part,
+            string(self.answer.as_deref()),
+            string(error.as_deref()),
+            self.elapsed.as_nanos(),
+            self.load.as_nanos(),
+            string(self.input_hash.map(|h| format!("{h:016x}")).as_deref()),
+        )
+    }
+}
+
+pub fn json_string(s: &str) -> String {
+    let mut str = String::with_capacity(s.len() + 2);
+    str.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => str.push_str("\\\""),
+            '\\' => str.push_str("\\\\"),
+            '\n' => str.push_str("\\n"),
+            '\r' => str.push_str("\\r"),
+            '\t' => str.push_str("\\t"),
+            c if (c as u32) < 0x20 => str.push_str(&format!("\\u{:04x}", c as u32)),
+            c => str.push(c),
+        }
+    }
+    str.push('"');
+    str
+}
+
+// FNV-1a, which unlike the standard library's hasher is stable across builds, so that hashes
+// can be compared between runs and machines.
+pub fn hash_input(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// Parses a selection of days given on the command line: "all", a single day ("4"), or a range
+// using Rust syntax ("3..8", "3..=8", "9.."). Ranges with no days in them are an error.
+pub fn parse_days(s: &str) -> Result<RangeInclusive<u32>> {
+    if s == "all" {
+        return Ok(1..=u32::MAX);
+    }
+
+    if let Some((start, end)) = s.split_once("..") {
+        let start = if start.is_empty() { 1 } else { start.parse()? };
+        let end = match end.strip_prefix('=') {
+            Some(end) => end.parse()?,
+            None if end.is_empty() => u32::MAX,
+            None => end.parse::<u32>()?.saturating_sub(1),
+        };
+        if start > end {
+            return err(&format!("empty day range {s}"));
+        }
+        return Ok(start..=end);
+    }
+
+    let day = s.parse()?;
+    Ok(day..=day)
+}
+
+// Which input to run a day on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    Puzzle,
+    Example(String),
+}
+
+// Loads a day's input, and how long it took.
+pub fn load(day: u32, which: &Input) -> (Result<String>, Duration) {
+    let start = Instant::now();
+    let result = match which {
+        Input::Puzzle => input(day as u8),
+        Input::Example(name) => examples::load(day, name),
+    };
+    (result, start.elapsed())
+}
+
+// Runs one part of a solution, treating a panic as a result rather than letting it unwind. Panics
+// raised by todo!() are reported as not implemented.
+pub fn run(solution: &dyn Solution, day: u32, part: u32, input: &str) -> Run {
+    let start = Instant::now();
+    let result = catch_unwind(AssertUnwindSafe(|| match part {
+        1 => solution.part1(input),
+        2 => solution.part2(input),
+        _ => err("invalid part number"),
+    }));
+    let elapsed = start.elapsed();
+
+    let (answer, status) = match result {
+        Ok(Ok(answer)) => (Some(answer), Status::Ok),
+        Ok(Err(e)) => (None, Status::Error(e.msg)),
+        Err(payload) => {
+            let msg = payload
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+                .unwrap_or_default();
+
+            if msg.starts_with("not yet implemented") {
+                (None, Status::NotImplemented)
+            } else {
+                (None, Status::Panicked(msg))
+            }
+        }
+    };
+
+    Run {
+        day,
+        part,
+        answer,
+        status,
+        elapsed,
+        load: Duration::ZERO,
+        input_hash: Some(hash_input(input)),
+    }
+}
+
+// Loads a day's input and runs one part of its solution on it.
+pub fn load_and_run(solution: &dyn Solution, day: u32, part: u32, which: &Input) -> Run {
+    let (input, load_time) = load(day, which);
+
+    match input {
+        Ok(input) => Run {
+            load: load_time,
+            ..run(solution, day, part, &input)
+        },
+        Err(e) => Run {
+            day,
+            part,
+            answer: None,
+            status: Status::Error(e.msg),
+            elapsed: Duration::ZERO,
+            load: load_time,
+            input_hash: None,
+        },
+    }
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+// Silences panic messages from this thread while running f, for callers that capture panics in a
+// Run's status rather than letting them print. The panic hook is process-wide, so it is replaced
+// once with one that checks a per-thread flag, rather than swapped back and forth; panics on other
+// threads, such as other tests, still print.
+pub fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    INSTALL_HOOK.call_once(|| {
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if !QUIET.get() {
+                hook(info);
+            }
+        }));
+    });
+
+    let was_quiet = QUIET.replace(true);
+    let result = f();
+    QUIET.set(was_quiet);
+    result
+}
+
+// Why a part shouldn't be run on an example, if it shouldn't: an example's answers may leave out
+// a part that the example doesn't apply to, such as one the puzzle runs with other parameters.
+fn example_skip(day: u32, part: u32, which: &Input) -> Option<Status> {
+    let Input::Example(name) = which else {
+        return None;
+    };
+
+    let expected = examples::expected(day, name).ok()?;
+    match expected.get(part) {
+        Some(_) => None,
+        None => Some(Status::Skipped(format!(
+            "{} has no answer for part {part}",
+            examples::example_path(day, name)
+                .with_extension("toml")
+                .display()
+        ))),
+    }
+}
+
+// Runs both parts of every selected day, in order. Parts which the registry says are not
+// implemented, or which an example leaves out, are skipped without loading their input.
+pub fn run_all(days: &Registry, selection: RangeInclusive<u32>, which: &Input) -> Vec<Run> {
+    let mut runs = Vec::new();
+    for (day, entry) in days.range(selection) {
+        for part in [1, 2] {
+            let skip = match entry.implemented(part) {
+                true => example_skip(*day, part, which),
+                false => Some(Status::NotImplemented),
+            };
+
+            runs.push(match skip {
+                None => quietly(|| load_and_run(entry.solution.as_ref(), *day, part, which)),
+                Some(status) => Run {
+                    day: *day,
+                    part,
+                    answer: None,
+                    status,
+                    elapsed: Duration::ZERO,
+                    load: Duration::ZERO,
+                    input_hash: None,
+                },
+            });
+        }
+    }
+    runs
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn of(samples: &[Duration]) -> Stats {
+        debug_assert!(!samples.is_empty());
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+            max: sorted[sorted.len() - 1],
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"min_ns\":{},\"median_ns\":{},\"mean_ns\":{},\"max_ns\":{}}}",
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.mean.as_nanos(),
+            self.max.as_nanos(),
+        )
+    }
+}
+
+pub struct Bench {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub runs: u32,
+    pub total: Stats,
+    pub load: Stats,
+    pub solve: Stats,
+}
+
+// Runs one part of a solution n times, splitting the time spent in each run into input loading
+// and solving. Fails on the first run that does not produce an answer.
+pub fn bench(solution: &dyn Solution, day: u32, part: u32, which: &Input, n: u32) -> Result<Bench> {
+    if n == 0 {
+        return err("bench needs at least one run");
+    }
+
+    let mut answer = String::new();
+    let (mut total, mut load, mut solve) = (Vec::new(), Vec::new(), Vec::new());
+
+    for _ in 0..n {
+        let run = quietly(|| load_and_run(solution, day, part, which));
+        match run.status {
+            Status::Ok => answer = run.answer.unwrap_or_default(),
+            Status::Error(msg) => return err(&format!("day {day} part {part}: {msg}")),
+            status => return err(&format!("day {day} part {part}: {status}")),
+        }
+
+        total.push(run.load + run.elapsed);
+        load.push(run.load);
+        solve.push(run.elapsed);
+    }
+
+    Ok(Bench {
+        day,
+        part,
+        answer,
+        runs: n,
+        total: Stats::of(&total),
+        load: Stats::of(&load),
+        solve: Stats::of(&solve),
+    })
+}
+
+impl Bench {
+    pub fn draw(&self) -> String {
+        let mut str = format!("answer: {} ({} runs)\n", self.answer, self.runs);
+        str.push_str(&format!(
+            "{:<6}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+            "", "min", "median", "mean", "max"
+        ));
+
+        for (name, stats) in [
+            ("total", &self.total),
+            ("load", &self.load),
+            ("solve", &self.solve),
+        ] {
+            str.push_str(&format!(
+                "{:<6}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+                name,
+                format!("{:.2?}", stats.min),
+                format!("{:.2?}", stats.median),
+                format!("{:.2?}", stats.mean),
+                format!("{:.2?}", stats.max),
+            ));
+        }
+
+        str
+    }
+
+    // Encodes the timings as a single line JSON object.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"runs\":{},\"total\":{},\"load\":{},\"solve\":{}}}",
+            self.day,
+            self.part,
+            json_string(&self.answer),
+            self.runs,
+            self.total.to_json(),
+            self.load.to_json(),
+            self.solve.to_json(),
+        )
+    }
+}
+
+// Describes a run's status, including any error message.
+pub fn describe(run: &Run) -> String {
+    match &run.status {
+        Status::Error(msg) | Status::Panicked(msg) | Status::Skipped(msg) => {
+            format!("{}: {msg}", run.status)
+        }
+        status => status.to_string(),
+    }
+}
+
+pub fn draw_table(days: &Registry, runs: &[Run]) -> String {
+    let rows: Vec<Vec<String>> = runs
+        .iter()
+        .map(|r| {
+            vec![
+                r.day.to_string(),
+                days.get(&r.day)
+                    .map(|d| d.title)
+                    .unwrap_or_default()
+                    .to_owned(),
+                r.part.to_string(),
+                r.answer.clone().unwrap_or_default(),
+                describe(r),
+                format!("{:.2?}", r.elapsed),
+            ]
+        })
+        .collect();
+
+    draw_columns(&["day", "title", "part", "answer", "status", "time"], &rows)
+}
+
+// Lays out rows of cells in left-aligned columns under a header.
+pub fn draw_columns(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let mut str = String::new();
+    let mut push_row = |cells: &[&str]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        str.push_str(line.join("  ").trim_end());
+        str.push('\n');
+    };
+
+    push_row(headers);
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    push_row(&rule.iter().map(String::as_str).collect::<Vec<_>>());
+    for row in rows {
+        push_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+
+    str
+}
+
+#[test]
+fn test_parse_days() {
+    assert_eq!(Ok(1..=u32::MAX), parse_days("all"));
+    assert_eq!(Ok(4..=4), parse_days("4"));
+    assert_eq!(Ok(3..=7), parse_days("3..8"));
+    assert_eq!(Ok(3..=8), parse_days("3..=8"));
+    assert_eq!(Ok(9..=u32::MAX), parse_days("9.."));
+    assert_eq!(Ok(1..=2), parse_days("..3"));
+    assert!(parse_days("x..3").is_err());
+
+    assert_eq!(Err(Error::new("empty day range 5..3")), parse_days("5..3"));
+    assert!(parse_days("1..1").is_err());
+    assert!(parse_days("..1").is_err());
+    assert!(parse_days("5..=3").is_err());
+    assert_eq!(Ok(5..=5), parse_days("5..=5"));
+}
+
+#[test]
+fn test_to_json() {
+    let run = Run {
+        day: 4,
+        part: 2,
+        answer: None,
+        status: Status::Error("bad \"input\"\n".to_owned()),
+        elapsed: Duration::from_micros(3),
+        load: Duration::from_nanos(20),
+        input_hash: Some(hash_input("")),
+    };
+
+    assert_eq!(
+        r#"{"day":4,"part":2,"answer":null,"error":"bad \"input\"\n","duration_ns":3000,"load_ns":20,"input_hash":"cbf29ce484222325"}"#,
+        run.to_json()
+    );
+}
+
+#[test]
+fn test_stats() {
+    let ms = Duration::from_millis;
+
+    assert_eq!(
+        Stats {
+            min: ms(1),
+            median: ms(3),
+            mean: ms(4),
+            max: ms(8),
+        },
+        Stats::of(&[ms(8), ms(1), ms(3)])
+    );
+
+    assert_eq!(ms(2), Stats::of(&[ms(1), ms(2), ms(2), ms(4)]).median);
+}
+
+#[test]
+fn test_bench_to_json() {
+    let bench = Bench {
+        day: 4,
+        part: 1,
+        answer: "13".to_owned(),
+        runs: 1,
+        total: Stats::of(&[Duration::from_nanos(7)]),
+        load: Stats::of(&[Duration::from_nanos(2)]),
+        solve: Stats::of(&[Duration::from_nanos(5)]),
+    };
+
+    assert_eq!(
+        r#"{"day":4,"part":1,"answer":"13","runs":1,"total":{"min_ns":7,"median_ns":7,"mean_ns":7,"max_ns":7},"load":{"min_ns":2,"median_ns":2,"mean_ns":2,"max_ns":2},"solve":{"min_ns":5,"median_ns":5,"mean_ns":5,"max_ns":5}}"#,
+        bench.to_json()
+    );
+}
+
+#[test]
+fn test_run_catches_todo() {
+    struct Unfinished;
+    impl Solution for Unfinished {
+        fn part1(&self, input: &str) -> Result<String> {
+            Ok(input.len().to_string())
+        }
+        fn part2(&self, _input: &str) -> Result<String> {
+            todo!("not implemented");
+        }
+    }
+
+    let runs = quietly(|| [run(&Unfinished, 1, 1, "abc"), run(&Unfinished, 1, 2, "abc")]);
+    assert_eq!(Some("3".to_owned()), runs[0].answer);
+    assert_eq!(Status::Ok, runs[0].status);
+    assert_eq!(None, runs[1].answer);
+    assert_eq!(Status::NotImplemented, runs[1].status);
+}
+
+#[test]
+fn test_quietly_only_silences_this_thread() {
+    quietly(|| {
+        assert!(QUIET.get());
+        let other = std::thread::spawn(|| QUIET.get()).join().unwrap();
+        assert!(!other);
+    });
+    assert!(!QUIET.get());
+}
+
+#[test]
+fn test_run_all_skips_parts_without_example_answers() {
+    // Day 8's example only has an answer for part 2, as part 1 connects a different number of
+    // pairs for it.
+    let runs = run_all(
+        &crate::registry(),
+        8..=8,
+        &Input::Example("example".to_owned()),
+    );
+
+    assert!(matches!(runs[0].status, Status::Skipped(_)));
+    assert!(!runs[0].failed());
+    assert_eq!(Some("25272".to_owned()), runs[1].answer);
+}
@@ -0,0 +1,209 @@
+use crate::shared::*;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = r#"use crate::shared::*;
+
+pub struct Day{nn};
+
+impl Solution for Day{nn} {
+    fn part1(&self, input: &str) -> Result<String> {
+        Ok(part1(input)?.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        Ok(part2(input)?.to_string())
+    }
+}
+
+fn part1(_input: &str) -> Result<usize> {
+    todo!("not implemented");
+}
+
+#[test]
+#[ignore = "not implemented"]
+fn test_part1() {
+    let input = crate::examples::load({day}, "example").unwrap();
+
+    assert_eq!(0, part1(&input).unwrap());
+}
+
+fn part2(_input: &str) -> Result<usize> {
+    todo!("not implemented");
+}
+
+#[test]
+#[ignore = "not implemented"]
+fn test_part2() {
+    let input = crate::examples::load({day}, "example").unwrap();
+
+    assert_eq!(0, part2(&input).unwrap());
+}
+"#;
+
+// The placeholder days were registered with before the template existed.
+const STUB: &str = r#"use crate::shared::*;
+
+pub struct Day{nn};
+
+impl Solution for Day{nn} {
+    fn part1(&self, _input: &str) -> Result<String> {
+        todo!("not implemented");
+    }
+
+    fn part2(&self, _input: &str) -> Result<String> {
+        todo!("not implemented");
+    }
+}
+"#;
+
+const EXAMPLE_ANSWERS: &str = "# part1 = \"\"\n# part2 = \"\"\n";
+
+fn fill(template: &str, day: u32) -> String {
+    template
+        .replace("{nn}", &format!("{day:02}"))
+        .replace("{day}", &day.to_string())
+}
+
+fn render(day: u32) -> String {
+    fill(TEMPLATE, day)
+}
+
+// A day's source is a stub, and safe to replace, only if it is exactly what was generated for it:
+// the template, or the placeholder. Any edit at all may be a solution in progress.
+fn is_stub(day: u32, src: &str) -> bool {
+    src == render(day) || src == fill(STUB, day)
+}
+
+// Adds a day to the days! invocation in main.rs, keeping the entries in order. Does nothing if the
+// day is already registered.
+fn register(main: &str, day: u32, title: &str) -> Result<String> {
+    let Some(start) = main.find("registry::days! {\n") else {
+        return err("could not find the days! registry in main.rs");
+    };
+
+    let mut lines: Vec<&str> = main.lines().collect();
+    let first = main[..start].lines().count() + 1;
+    let Some(end) = lines[first..].iter().position(|l| *l == "}") else {
+        return err("could not find the end of the days! registry in main.rs");
+    };
+
+    let number = |l: &str| {
+        l.trim()
+            .split(" =>")
+            .next()
+            .and_then(|n| n.parse::<u32>().ok())
+    };
+    let entries = &lines[first..first + end];
+    if entries.iter().any(|l| number(l) == Some(day)) {
+        return Ok(main.to_owned());
+    }
+
+    let at = first
+        + entries
+            .iter()
+            .position(|l| number(l).is_some_and(|n| n > day))
+            .unwrap_or(end);
+    let entry = format!("    {day} => day{day:02}::Day{day:02} {{ title: {title:?}, parts: [] }},");
+    lines.insert(at, &entry);
+
+    let mut str = lines.join("\n");
+    str.push('\n');
+    Ok(str)
+}
+
+fn write_new(path: &Path, contents: &str, written: &mut Vec<PathBuf>) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir)?;
+    }
+    write(path, contents)?;
+    written.push(path.to_owned());
+    Ok(())
+}
+
+// Generates the source, example files and an empty input file for a day and registers it in
+// main.rs, returning the files written. Refuses to replace a day that has code beyond a stub.
+// Paths are relative to the repository root, which must be the working directory.
+pub fn new_day(day: u32, title: &str) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return err(&format!("day {day} is not between 1 and 25"));
+    }
+
+    let main_path = PathBuf::from("src/main.rs");
+    if !main_path.exists() {
+        return err("aoc2025 new must be run from the root of the repository");
+    }
+
+    let src_path = PathBuf::from(format!("src/day{day:02}.rs"));
+    if src_path.exists() && !is_stub(day, &read_to_string(&src_path)?) {
+        return err(&format!("{} already has code", src_path.display()));
+    }
+
+    let mut written = Vec::new();
+
+    write_new(&src_path, &render(day), &mut written)?;
+
+    let example = crate::examples::example_path(day, "example");
+    if !example.exists() {
+        write_new(&example, "", &mut written)?;
+        write_new(
+            &example.with_extension("toml"),
+            EXAMPLE_ANSWERS,
+            &mut written,
+        )?;
+    }
+
+    let input = input_path(day as u8);
+    if !input.exists() {
+        write_new(&input, "", &mut written)?;
+    }
+
+    let main = read_to_string(&main_path)?;
+    let registered = register(&main, day, title)?;
+    if registered != main {
+        write_new(&main_path, &registered, &mut written)?;
+    }
+
+    Ok(written)
+}
+
+#[test]
+fn test_is_stub() {
+    assert!(is_stub(13, &render(13)));
+    assert!(is_stub(9, &fill(STUB, 9)));
+    assert!(!is_stub(12, &render(13)));
+
+    // A solved part 1 alongside an unsolved part 2 is still code worth keeping.
+    let part1_solved = render(13).replacen(
+        "fn part1(_input: &str) -> Result<usize> {\n    todo!(\"not implemented\");",
+        "fn part1(input: &str) -> Result<usize> {\n    Ok(input.len())",
+        1,
+    );
+    assert_ne!(render(13), part1_solved);
+    assert!(part1_solved.contains("todo!"));
+    assert!(!is_stub(13, &part1_solved));
+
+    assert!(!is_stub(13, &format!("{}\nstruct Parsed;\n", render(13))));
+}
+
+#[test]
+fn test_new_day_range() {
+    assert!(new_day(0, "").is_err());
+    assert!(new_day(256 + 4, "").is_err());
+}
+
+#[test]
+fn test_register() {
+    let main = "mod shared;\n\nregistry::days! {\n    1 => day01::Day01 { title: \"A\", parts: [1, 2] },\n    3 => day03::Day03 { title: \"C\", parts: [] },\n}\n\nfn main() {}\n";
+
+    assert_eq!(
+        "mod shared;\n\nregistry::days! {\n    1 => day01::Day01 { title: \"A\", parts: [1, 2] },\n    2 => day02::Day02 { title: \"B\", parts: [] },\n    3 => day03::Day03 { title: \"C\", parts: [] },\n}\n\nfn main() {}\n",
+        register(main, 2, "B").unwrap()
+    );
+    assert!(
+        register(main, 4, "")
+            .unwrap()
+            .contains("    3 => day03::Day03 { title: \"C\", parts: [] },\n    4 => day04::Day04 { title: \"\", parts: [] },\n}\n")
+    );
+    assert_eq!(main, register(main, 3, "C").unwrap());
+}
@@ -0,0 +1,272 @@
+use crate::shared::*;
+use std::mem::swap;
+
+// A cellular automaton over a grid. Each generation, rule(cell, n) gives the next value of every
+// cell at once, where n is how many of its neighbours are live. Day 4's paper rolls, which are
+// removed once fewer than four rolls surround them:
+//
+//     let mut rolls = Automaton::new(grid, |c| c == '@', |c, n| match c {
+//         '@' if n < 4 => '.',
+//         _ => c,
+//     });
+//     let generations = rolls.run_until_stable();
+pub struct Automaton<T, S, L, R> {
+    current: Grid<T, S>,
+    // The buffer each generation is written to before being swapped in. Only a scratch space: it
+    // is completely overwritten each time.
+    next: Grid<T, S>,
+    generation: usize,
+    neighbourhood: Neighbourhood,
+    live: L,
+    rule: R,
+    frontier: bool,
+    // The cells which changed in the last generation, when only they and their neighbours are
+    // re-examined. None until the first generation, which examines every cell.
+    changed: Option<Vec<Position>>,
+}
+
+impl<T, S, L, R> Automaton<T, S, L, R>
+where
+    T: Clone + PartialEq,
+    S: Storage<T>,
+    L: Fn(T) -> bool,
+    R: Fn(T, usize) -> T,
+{
+    pub fn new(grid: Grid<T, S>, live: L, rule: R) -> Automaton<T, S, L, R> {
+        Automaton {
+            next: grid.clone(),
+            current: grid,
+            generation: 0,
+            neighbourhood: Neighbourhood::Eight,
+            live,
+            rule,
+            frontier: false,
+            changed: None,
+        }
+    }
+
+    pub fn neighbourhood(self, neighbourhood: Neighbourhood) -> Automaton<T, S, L, R> {
+        Automaton {
+            neighbourhood,
+            ..self
+        }
+    }
+
+    // Only re-examines the cells next to those that changed in the previous generation. Much
+    // faster when changes are few and local, but the rule must leave a cell alone when neither it
+    // nor its neighbours have changed.
+    pub fn frontier(self) -> Automaton<T, S, L, R> {
+        Automaton {
+            frontier: true,
+            ..self
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T, S> {
+        &self.current
+    }
+
+    pub fn into_grid(self) -> Grid<T, S> {
+        self.current
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Advances one generation, returning how many cells changed.
+    pub fn step(&mut self) -> usize {
+        self.generation += 1;
+
+        match self.changed.take() {
+            Some(changed) if self.frontier => self.step_frontier(changed),
+            _ => self.step_all(),
+        }
+    }
+
+    fn step_all(&mut self) -> usize {
+        let counts = self
+            .current
+            .neighbour_counts(self.neighbourhood, &self.live);
+        let mut changed = Vec::new();
+
+        for pos in self.current.positions() {
+            let cell = self.current.at(pos);
+            let next = (self.rule)(cell.clone(), counts.at(pos) as usize);
+            if next != cell {
+                changed.push(pos);
+            }
+            self.next.set(pos, next);
+        }
+        swap(&mut self.current, &mut self.next);
+
+        let n = changed.len();
+        self.changed = Some(changed);
+        n
+    }
+
+    fn step_frontier(&mut self, changed: Vec<Position>) -> usize {
+        // A cell's count can only change when a cell it counts as a neighbour changes.
+        let mut queued = BitGrid::new(self.current.width(), self.current.height());
+        let mut candidates = Vec::new();
+        for pos in changed {
+            let from = self
+                .neighbourhood
+                .directions()
+                .iter()
+                .filter_map(|d| self.current.step(pos, Direction(-d.0, -d.1)));
+            for p in std::iter::once(pos).chain(from) {
+                if !queued.at(p) {
+                    queued.set(p, true);
+                    candidates.push(p);
+                }
+            }
+        }
+
+        // Work out every change before making any, so all cells see the same generation.
+        let updates: Vec<(Position, T)> = candidates
+            .into_iter()
+            .filter_map(|pos| {
+                let cell = self.current.at(pos);
+                let n = self
+                    .current
+                    .count_neighbours(pos, self.neighbourhood, &self.live);
+                let next = (self.rule)(cell.clone(), n);
+                (next != cell).then_some((pos, next))
+            })
+            .collect();
+
+        let n = updates.len();
+        let mut changed = Vec::with_capacity(n);
+        for (pos, next) in updates {
+            self.current.set(pos, next);
+            changed.push(pos);
+        }
+
+        self.changed = Some(changed);
+        n
+    }
+
+    // Steps until a generation changes nothing, returning how many generations did.
+    pub fn run_until_stable(&mut self) -> usize {
+        let mut generations = 0;
+        while self.step() > 0 {
+            generations += 1;
+        }
+
+        generations
+    }
+}
+
+impl<T, S, L, R> Automaton<T, S, L, R>
+where
+    T: Clone + Eq,
+    S: Storage<T> + Eq,
+    L: Fn(T) -> bool,
+    R: Fn(T, usize) -> T,
+{
+    // The generation after a grid, without touching the automaton's own.
+    fn next_generation(&self, grid: &Grid<T, S>) -> Grid<T, S> {
+        let counts = grid.neighbour_counts(self.neighbourhood, &self.live);
+        let mut next = grid.clone();
+        for pos in grid.positions() {
+            next.set(pos, (self.rule)(grid.at(pos), counts.at(pos) as usize));
+        }
+
+        next
+    }
+
+    // Advances n generations. If the grid returns to an earlier state on the way, the rest are
+    // skipped by going round the cycle, which is returned.
+    pub fn run_n(&mut self, n: usize) -> Option<Cycle> {
+        let start = self.generation;
+        let (grid, cycle) =
+            state_and_cycle_after(self.current.clone(), n, |g| self.next_generation(g));
+
+        self.current = grid;
+        self.generation = start + n;
+        self.changed = None;
+        cycle.map(|cycle| Cycle {
+            start: start + cycle.start,
+            ..cycle
+        })
+    }
+}
+
+#[cfg(test)]
+fn life(
+    grid: CharGrid,
+) -> Automaton<char, Vec<char>, impl Fn(char) -> bool, impl Fn(char, usize) -> char> {
+    Automaton::new(
+        grid,
+        |c| c == '#',
+        |c, n| match (c, n) {
+            ('#', 2 | 3) | ('.', 3) => '#',
+            _ => '.',
+        },
+    )
+}
+
+#[test]
+fn test_step() {
+    // A blinker flips between horizontal and vertical.
+    let mut blinker = life(CharGrid::from_str(".....\n.....\n.###.\n.....\n.....").unwrap());
+
+    assert_eq!(4, blinker.step());
+    assert_eq!(
+        ".....\n..#..\n..#..\n..#..\n.....\n\n",
+        blinker.grid().draw()
+    );
+    assert_eq!(4, blinker.step());
+    assert_eq!(
+        ".....\n.....\n.###.\n.....\n.....\n\n",
+        blinker.grid().draw()
+    );
+    assert_eq!(2, blinker.generation());
+}
+
+#[test]
+fn test_run_until_stable() {
+    let grid = CharGrid::from_str("..@@.@@@@.\n@@@.@.@.@@\n@@@@@.@.@@").unwrap();
+    let remove = |c, n| if c == '@' && n < 4 { '.' } else { c };
+
+    let mut all = Automaton::new(grid.clone(), |c| c == '@', remove);
+    let mut frontier = Automaton::new(grid, |c| c == '@', remove).frontier();
+    let generations = all.run_until_stable();
+
+    assert!(generations > 1);
+    assert_eq!(generations, frontier.run_until_stable());
+    assert_eq!(all.grid(), frontier.grid());
+}
+
+#[test]
+fn test_run_n() {
+    // A glider on a wrapping grid comes back to where it started after 4 generations per cell
+    // it has moved: 4 * 6 on a 6x6 grid.
+    let glider = CharGrid::from_str(".#....\n..#...\n###...\n......\n......\n......")
+        .unwrap()
+        .wrapping();
+    let mut fast = life(glider.clone());
+
+    assert_eq!(
+        Some(Cycle {
+            start: 0,
+            length: 24
+        }),
+        fast.run_n(1_000_000_001)
+    );
+    assert_eq!(1_000_000_001, fast.generation());
+
+    let mut slow = life(glider);
+    for _ in 0..(1_000_000_001 % 24) {
+        slow.step();
+    }
+    assert_eq!(slow.grid(), fast.grid());
+
+    // Too few generations to come round again: every one is stepped through.
+    let mut short = life(CharGrid::from_str("......\n.###..\n......").unwrap());
+    short.step();
+    assert_eq!(None, short.run_n(1));
+    assert_eq!("......\n.###..\n......\n\n", short.grid().draw());
+    assert_eq!(2, short.generation());
+}
@@ -0,0 +1,185 @@
+use crate::shared::*;
+
+// A repeating sequence of states: the one after start steps comes round again every length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // The earliest step which reaches the same state as step n.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            return n;
+        }
+
+        self.start + (n - self.start) % self.length
+    }
+}
+
+// Brent's algorithm: steps a hare ahead of a tortoise, which jumps to the hare at each power of
+// two, until they meet. Gives up with the state after limit steps if no cycle is found by then.
+fn brent<T: Clone + Eq>(
+    initial: &T,
+    step: &impl Fn(&T) -> T,
+    limit: usize,
+) -> std::result::Result<Cycle, T> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut steps = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+
+    while tortoise != hare {
+        if steps >= limit {
+            return Err(hare);
+        }
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+        steps += 1;
+    }
+
+    // With the hare a cycle's length ahead, they first meet where the cycle starts.
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Ok(Cycle { start, length })
+}
+
+// Finds the cycle that repeatedly stepping from an initial state falls into. Never returns if
+// the states don't repeat.
+pub fn find_cycle<T: Clone + Eq>(initial: &T, step: impl Fn(&T) -> T) -> Cycle {
+    match brent(initial, &step, usize::MAX) {
+        Ok(cycle) => cycle,
+        Err(_) => unreachable!("ran out of steps looking for a cycle"),
+    }
+}
+
+// The state after n steps from an initial state, skipping round any cycle found on the way
+// instead of stepping through every repeat.
+//
+//     let platform = state_after(grid, 1_000_000_000, |g| spin(g));
+pub fn state_after<T: Clone + Eq>(initial: T, n: usize, step: impl Fn(&T) -> T) -> T {
+    state_and_cycle_after(initial, n, step).0
+}
+
+// Like state_after, but also gives the cycle skipped round, if one was found within n steps.
+pub fn state_and_cycle_after<T: Clone + Eq>(
+    initial: T,
+    n: usize,
+    step: impl Fn(&T) -> T,
+) -> (T, Option<Cycle>) {
+    if n == 0 {
+        return (initial, None);
+    }
+
+    match brent(&initial, &step, n) {
+        Err(state) => (state, None),
+        Ok(cycle) => {
+            let mut state = initial;
+            for _ in 0..cycle.reduce(n) {
+                state = step(&state);
+            }
+            (state, Some(cycle))
+        }
+    }
+}
+
+#[test]
+fn test_find_cycle() {
+    // 0, 1, .. 10, then round 8, 9, 10 forever.
+    let step = |x: &u64| if *x < 10 { x + 1 } else { 8 };
+
+    let cycle = find_cycle(&0, step);
+    assert_eq!(
+        Cycle {
+            start: 8,
+            length: 3
+        },
+        cycle
+    );
+    assert_eq!(5, cycle.reduce(5));
+    assert_eq!(9, cycle.reduce(12));
+
+    assert_eq!(0, state_after(0, 0, step));
+    assert_eq!(5, state_after(0, 5, step));
+    assert_eq!(10, state_after(0, 1_000_000_000, step));
+
+    assert_eq!((5, None), state_and_cycle_after(0, 5, step));
+    assert_eq!(
+        (10, Some(cycle)),
+        state_and_cycle_after(0, 1_000_000_000, step)
+    );
+}
+
+#[test]
+fn test_struct_state() {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Dial {
+        position: u32,
+        turns: u32,
+    }
+
+    // The position goes round 7 places, but turns counts up to 3 first.
+    let step = |d: &Dial| Dial {
+        position: (d.position + 3) % 7,
+        turns: (d.turns + 1).min(3),
+    };
+    let start = Dial {
+        position: 0,
+        turns: 0,
+    };
+
+    assert_eq!(
+        Cycle {
+            start: 3,
+            length: 7
+        },
+        find_cycle(&start, step)
+    );
+    assert_eq!(
+        Dial {
+            position: (1_000_000 * 3) % 7,
+            turns: 3
+        },
+        state_after(start, 1_000_000, step)
+    );
+}
+
+#[test]
+fn test_grid_state() {
+    let grid = CharGrid::from_str("ab\ncd").unwrap();
+    let rotate = |g: &CharGrid| g.rotate_cw();
+
+    assert_eq!(
+        Cycle {
+            start: 0,
+            length: 4
+        },
+        find_cycle(&grid, rotate)
+    );
+    assert_eq!(
+        grid.rotate_ccw(),
+        state_after(grid.clone(), 1_000_000_003, rotate)
+    );
+
+    let mut meta: MetaGrid<usize> = MetaGrid::of(grid);
+    meta.set_meta(Position::at(0, 0), 1);
+    let spin = |m: &MetaGrid<usize>| m.rotate_180();
+    assert_eq!(meta, state_after(meta.clone(), 1_000_000_000, spin));
+}
@@ -0,0 +1,702 @@
+use crate::shared::*;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::hash::Hash;
+
+// Where a Grid keeps its cells, in row-major order. Vec stores any cell type; Bits packs bools
+// 64 to a word. Grids use Vec unless told otherwise, so a Grid<bool> takes a byte per cell: use
+// BitGrid for the packed version.
+pub trait Storage<T>: Clone + FromIterator<T> {
+    fn len(&self) -> usize;
+    fn load(&self, idx: usize) -> T;
+    fn store(&mut self, idx: usize, v: T);
+    fn store_all(&mut self, v: T);
+}
+
+impl<T: Clone> Storage<T> for Vec<T> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn load(&self, idx: usize) -> T {
+        self[idx].clone()
+    }
+
+    fn store(&mut self, idx: usize, v: T) {
+        self[idx] = v;
+    }
+
+    fn store_all(&mut self, v: T) {
+        <[T]>::fill(self, v);
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Bits {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bits {
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+impl FromIterator<bool> for Bits {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = Bits::default();
+        for (idx, v) in iter.into_iter().enumerate() {
+            if idx % 64 == 0 {
+                bits.words.push(0);
+            }
+            bits.len += 1;
+            bits.store(idx, v);
+        }
+        bits
+    }
+}
+
+impl Storage<bool> for Bits {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn load(&self, idx: usize) -> bool {
+        debug_assert!(idx < self.len);
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    fn store(&mut self, idx: usize, v: bool) {
+        debug_assert!(idx < self.len);
+        if v {
+            self.words[idx / 64] |= 1 << (idx % 64);
+        } else {
+            self.words[idx / 64] &= !(1 << (idx % 64));
+        }
+    }
+
+    fn store_all(&mut self, v: bool) {
+        self.words.fill(if v { u64::MAX } else { 0 });
+        // Keep the bits past the end clear, so that count_ones and comparisons stay exact.
+        if let Some(last) = self.words.last_mut()
+            && !self.len.is_multiple_of(64)
+        {
+            *last &= (1 << (self.len % 64)) - 1;
+        }
+    }
+}
+
+// Which cells count as a cell's neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    // Up, down, left and right.
+    Four,
+    // Diagonals too.
+    Eight,
+    // Any set of offsets, such as a knight's moves.
+    Custom(&'static [Direction]),
+}
+
+const FOUR: [Direction; 4] = [UP, LEFT, RIGHT, DOWN];
+const EIGHT: [Direction; 8] = [
+    Direction(-1, -1),
+    UP,
+    Direction(1, -1),
+    LEFT,
+    RIGHT,
+    Direction(-1, 1),
+    DOWN,
+    Direction(1, 1),
+];
+
+impl Neighbourhood {
+    pub fn directions(&self) -> &'static [Direction] {
+        match self {
+            Neighbourhood::Four => &FOUR,
+            Neighbourhood::Eight => &EIGHT,
+            Neighbourhood::Custom(directions) => directions,
+        }
+    }
+}
+
+// A 2d grid of cells of any type, indexed by Position from (0, 0) at the top left. Cells are
+// stored in S, which is a Vec<T> by default; see Storage.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T, S = Vec<T>> {
+    width: i32,
+    height: i32,
+    cells: S,
+    // Whether walking off one edge comes back on the opposite edge.
+    wrap: bool,
+    _cell: std::marker::PhantomData<T>,
+}
+
+// A grid of bools packed into bits, taking an eighth of the space of a Grid<bool>.
+pub type BitGrid = Grid<bool, Bits>;
+
+// A grid of bytes, for ASCII puzzles where chars would take four times the space.
+pub type ByteGrid = Grid<u8>;
+
+pub type GridView<'a, T> = View<'a, Grid<T>>;
+
+impl<T, S: Storage<T>> Grid<T, S> {
+    pub fn filled(width: i32, height: i32, v: T) -> Grid<T, S>
+    where
+        T: Clone,
+    {
+        debug_assert!(width >= 0);
+        debug_assert!(height >= 0);
+
+        let cells = std::iter::repeat_n(v, (width * height) as usize).collect();
+        Grid::from_cells(width, height, cells)
+    }
+
+    // A grid of default cells. For chars that is '\0'; see CharGrid::blank for spaces.
+    pub fn new(width: i32, height: i32) -> Grid<T, S>
+    where
+        T: Default + Clone,
+    {
+        Self::filled(width, height, T::default())
+    }
+
+    fn from_cells(width: i32, height: i32, cells: S) -> Grid<T, S> {
+        debug_assert!(cells.len() == (width * height) as usize);
+
+        Grid {
+            width,
+            height,
+            cells,
+            wrap: false,
+            _cell: std::marker::PhantomData,
+        }
+    }
+
+    // Builds a grid by calling f for each position, in row-major order.
+    pub fn from_fn(width: i32, height: i32, mut f: impl FnMut(Position) -> T) -> Grid<T, S> {
+        let cells = (0..height).flat_map(|y| (0..width).map(move |x| Position::at(x, y)));
+        let cells = cells.map(&mut f).collect();
+        Grid::from_cells(width, height, cells)
+    }
+
+    // Builds a grid from rows of cells, which must all be the same length.
+    pub fn from_rows<R: IntoIterator<Item = T>>(
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<Grid<T, S>> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for row in rows {
+            let before = cells.len();
+            cells.extend(row);
+            let len = (cells.len() - before) as i32;
+
+            match width {
+                None => width = Some(len),
+                Some(w) if w != len => {
+                    return err(&format!(
+                        "row {}: expected width {w}, found {len}",
+                        height + 1
+                    ));
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        let width = width.unwrap_or(0);
+        Ok(Grid::from_cells(width, height, cells.into_iter().collect()))
+    }
+
+    // Parses a grid from lines of text, converting each char to a cell. See GridParser for
+    // parsing bytes instead.
+    pub fn parse(str: &str, cell: impl Fn(char) -> T) -> Result<Grid<T, S>> {
+        GridParser::new(Encoding::Unicode).parse(str, cell)
+    }
+
+    // Makes the grid toroidal, so that neighbours, lines and searches wrap around its edges.
+    pub fn wrapping(self) -> Grid<T, S> {
+        Grid { wrap: true, ..self }
+    }
+
+    pub fn wraps(&self) -> bool {
+        self.wrap
+    }
+
+    // The position any position corresponds to in a wrapping grid.
+    pub fn wrap(&self, pos: Position) -> Position {
+        Position::at(0, 0).wrapping_add_pos(pos, (Position::at(0, 0), self.dimensions()))
+    }
+
+    fn dimensions(&self) -> Dimensions {
+        Dimensions::of(self.width, self.height)
+    }
+
+    // The position a step in a direction leads to, or None if it leaves a grid which doesn't
+    // wrap.
+    pub fn step(&self, pos: Position, dir: Direction) -> Option<Position> {
+        if self.wrap {
+            return Some(pos.wrapping_add_direction(dir, (Position::at(0, 0), self.dimensions())));
+        }
+
+        Some(pos + dir).filter(|p| self.in_bounds(*p))
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn x_max(&self) -> i32 {
+        self.width - 1
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn y_max(&self) -> i32 {
+        self.height - 1
+    }
+
+    pub fn in_bounds(&self, p: Position) -> bool {
+        p.x >= 0 && p.y >= 0 && p.x < self.width && p.y < self.height
+    }
+
+    fn idx(&self, pos: Position) -> usize {
+        debug_assert!(
+            self.in_bounds(pos),
+            "out of bounds position {pos} in {}x{} grid",
+            self.width,
+            self.height
+        );
+        (pos.y * self.width + pos.x) as usize
+    }
+
+    fn pos(&self, idx: usize) -> Position {
+        Position::at(idx as i32 % self.width, idx as i32 / self.width)
+    }
+
+    pub fn at(&self, pos: Position) -> T {
+        self.cells.load(self.idx(pos))
+    }
+
+    pub fn at_xy(&self, x: i32, y: i32) -> T {
+        self.at(Position::at(x, y))
+    }
+
+    pub fn at_checked(&self, pos: Position) -> Option<T> {
+        if self.in_bounds(pos) {
+            Some(self.at(pos))
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, pos: Position, v: T) {
+        let idx = self.idx(pos);
+        self.cells.store(idx, v)
+    }
+
+    pub fn set_xy(&mut self, x: i32, y: i32, v: T) {
+        self.set(Position::at(x, y), v)
+    }
+
+    pub fn set_checked(&mut self, pos: Position, v: T) -> bool {
+        if self.in_bounds(pos) {
+            self.set(pos, v);
+            return true;
+        }
+
+        return false;
+    }
+
+    pub fn fill(&mut self, v: T) {
+        self.cells.store_all(v);
+    }
+
+    // Every position in the grid, in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + use<T, S> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Position::at(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, T)> + '_ {
+        (0..self.cells.len()).map(|idx| (self.pos(idx), self.cells.load(idx)))
+    }
+
+    pub fn map<U, R: Storage<U>>(&self, f: impl Fn(T) -> U) -> Grid<U, R> {
+        let cells = (0..self.cells.len()).map(|idx| f(self.cells.load(idx)));
+        Grid {
+            wrap: self.wrap,
+            ..Grid::from_cells(self.width, self.height, cells.collect())
+        }
+    }
+
+    // The cells from a position to the edge of the grid in a direction, starting with the cell
+    // at the position itself. In a wrapping grid, the line goes on until it comes back round
+    // to where it started.
+    pub fn line(&self, start: Position, dir: Direction) -> Vec<T> {
+        let mut vec = Vec::new();
+        let mut pos = Some(start).filter(|p| self.in_bounds(*p));
+
+        while let Some(here) = pos {
+            vec.push(self.at(here));
+            pos = self.step(here, dir).filter(|p| *p != start);
+        }
+
+        vec
+    }
+
+    // The neighbours of a position, in the order of the neighbourhood's directions. Those
+    // outside the grid are left out, unless it wraps.
+    pub fn neighbours(
+        &self,
+        pos: Position,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = Position> + '_ {
+        neighbourhood
+            .directions()
+            .iter()
+            .filter_map(move |d| self.step(pos, *d))
+    }
+
+    pub fn count_neighbours(
+        &self,
+        pos: Position,
+        neighbourhood: Neighbourhood,
+        pred: impl Fn(T) -> bool,
+    ) -> usize {
+        self.neighbours(pos, neighbourhood)
+            .filter(|p| pred(self.at(*p)))
+            .count()
+    }
+
+    // Counts, for every cell at once, how many of its neighbours match. Cheaper than calling
+    // count_neighbours for each cell when few cells match.
+    pub fn neighbour_counts(
+        &self,
+        neighbourhood: Neighbourhood,
+        pred: impl Fn(T) -> bool,
+    ) -> Grid<u8> {
+        let mut counts = Grid::new(self.width, self.height);
+
+        for idx in 0..self.cells.len() {
+            if !pred(self.cells.load(idx)) {
+                continue;
+            }
+            // This cell is a neighbour of each cell it is offset from.
+            let pos = self.pos(idx);
+            for d in neighbourhood.directions() {
+                if let Some(from) = self.step(pos, Direction(-d.0, -d.1)) {
+                    *counts.get_mut(from) += 1;
+                }
+            }
+        }
+
+        counts
+    }
+
+    pub fn windows(&self, width: i32, height: i32) -> Windows<'_, Grid<T, S>> {
+        Windows::new(self, (self.width, self.height), (width, height))
+    }
+}
+
+impl<T: PartialEq + Display, S: Storage<T>> Grid<T, S> {
+    pub fn find_one(&self, v: T) -> Result<Position> {
+        match (0..self.cells.len()).find(|idx| self.cells.load(*idx) == v) {
+            Some(idx) => Ok(self.pos(idx)),
+            None => Err(Error::new(&format!("'{v}' not found in grid"))),
+        }
+    }
+
+    pub fn find_all(&self, v: T) -> Vec<Position> {
+        (0..self.cells.len())
+            .filter(|idx| self.cells.load(*idx) == v)
+            .map(|idx| self.pos(idx))
+            .collect()
+    }
+
+    pub fn count(&self, v: T) -> usize {
+        (0..self.cells.len())
+            .filter(|idx| self.cells.load(*idx) == v)
+            .count()
+    }
+}
+
+impl<T: Eq + Hash, S: Storage<T>> Grid<T, S> {
+    pub fn uniq(&self) -> Vec<T> {
+        let mut seen = HashSet::new();
+
+        for idx in 0..self.cells.len() {
+            seen.insert(self.cells.load(idx));
+        }
+
+        seen.into_iter().collect()
+    }
+}
+
+impl<T: Display, S: Storage<T>> Grid<T, S> {
+    pub fn draw(&self) -> String {
+        let mut str = String::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                str.push_str(&self.at_xy(x, y).to_string())
+            }
+            str.push('\n')
+        }
+        str.push('\n');
+
+        str
+    }
+}
+
+impl ByteGrid {
+    // Parses a grid of ASCII text, one byte per cell.
+    pub fn from_str(str: &str) -> Result<ByteGrid> {
+        GridParser::new(Encoding::Bytes).parse(str, |c| c as u8)
+    }
+}
+
+// Grids stored in a Vec can also lend out their cells.
+impl<T: Clone> Grid<T> {
+    pub fn get(&self, pos: Position) -> &T {
+        let idx = self.idx(pos);
+        &self.cells[idx]
+    }
+
+    pub fn get_mut(&mut self, pos: Position) -> &mut T {
+        let idx = self.idx(pos);
+        &mut self.cells[idx]
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+}
+
+impl BitGrid {
+    pub fn count_set(&self) -> usize {
+        self.cells.count_ones()
+    }
+}
+
+impl<T, S: Storage<T>> AsRef<Grid<T, S>> for Grid<T, S> {
+    fn as_ref(&self) -> &Grid<T, S> {
+        self
+    }
+}
+
+// Windows is an iterator over a grid that yields every sub-grid of the given size as a read-only
+// view.
+pub struct Windows<'a, G> {
+    grid: &'a G,
+    grid_size: (i32, i32),
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl<'a, G> Windows<'a, G> {
+    pub(super) fn new(grid: &'a G, grid_size: (i32, i32), size: (i32, i32)) -> Windows<'a, G> {
+        Windows {
+            grid,
+            grid_size,
+            x: 0,
+            y: 0,
+            width: size.0,
+            height: size.1,
+        }
+    }
+}
+
+impl<'a, G> Iterator for Windows<'a, G> {
+    type Item = View<'a, G>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (grid_width, grid_height) = self.grid_size;
+        if self.x > grid_width - self.width || self.y > grid_height - self.height {
+            return None;
+        }
+
+        let result = Some(View {
+            grid: self.grid,
+            offset: Position::at(self.x, self.y),
+            width: self.width,
+            height: self.height,
+        });
+
+        self.x += 1;
+        if self.x > grid_width - self.width {
+            self.x = 0;
+            self.y += 1;
+        }
+
+        result
+    }
+}
+
+// A read-only view of part of a grid, positioned relative to its own top left corner.
+#[derive(Debug)]
+pub struct View<'a, G> {
+    pub(super) grid: &'a G,
+    pub(super) offset: Position,
+    pub(super) width: i32,
+    pub(super) height: i32,
+}
+
+impl<G> View<'_, G> {
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> + use<G> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Position::at(x, y)))
+    }
+}
+
+impl<T, S: Storage<T>> View<'_, Grid<T, S>> {
+    pub fn at(&self, pos: Position) -> T {
+        self.grid.at(pos + self.offset)
+    }
+
+    pub fn cells(&self) -> Vec<T> {
+        self.positions().map(|p| self.at(p)).collect()
+    }
+}
+
+#[test]
+fn test_from_rows() {
+    let grid: Grid<u32> = Grid::from_rows([[1, 2, 3], [4, 5, 6]]).unwrap();
+
+    assert_eq!((3, 2), (grid.width(), grid.height()));
+    assert_eq!(6, grid.at_xy(2, 1));
+    assert_eq!(Some(4), grid.at_checked(Position::at(0, 1)));
+    assert_eq!(None, grid.at_checked(Position::at(3, 0)));
+
+    let ragged: Result<Grid<u32>> = Grid::from_rows([vec![1, 2], vec![3]]);
+    assert_eq!(Err(Error::new("row 2: expected width 2, found 1")), ragged);
+
+    let empty: Grid<u32> = Grid::from_rows(Vec::<Vec<u32>>::new()).unwrap();
+    assert_eq!((0, 0), (empty.width(), empty.height()));
+}
+
+#[test]
+fn test_find() {
+    let grid = ByteGrid::from_str("ab\nba").unwrap();
+
+    assert_eq!(Ok(Position::at(1, 0)), grid.find_one(b'b'));
+    assert_eq!(
+        vec![Position::at(0, 0), Position::at(1, 1)],
+        grid.find_all(b'a')
+    );
+    assert_eq!(2, grid.count(b'a'));
+    assert!(grid.find_one(b'c').is_err());
+}
+
+#[test]
+fn test_bit_grid() {
+    let mut grid = BitGrid::parse("#..\n.#.\n..#", |c| c == '#').unwrap();
+
+    assert_eq!(3, grid.count_set());
+    assert!(grid.at_xy(1, 1));
+    grid.set_xy(1, 1, false);
+    assert!(!grid.at_xy(1, 1));
+    assert_eq!(2, grid.count_set());
+
+    let mut big = BitGrid::new(10, 10);
+    big.fill(true);
+    assert_eq!(100, big.count_set());
+    big.set_xy(9, 9, false);
+    assert_eq!(99, big.count_set());
+    assert_eq!(99, big.count(true));
+}
+
+#[test]
+fn test_map_and_line() {
+    let grid: Grid<u32> = Grid::from_rows([[1, 2], [3, 4]]).unwrap();
+    let doubled: Grid<u32> = grid.map(|v| v * 2);
+
+    assert_eq!(vec![2, 8], doubled.line(Position::at(0, 0), RIGHT + DOWN));
+    assert_eq!(vec![8, 4], doubled.line(Position::at(1, 1), UP));
+    assert_eq!("24\n68\n\n", doubled.draw());
+}
+
+#[test]
+fn test_neighbours() {
+    let grid = CharGrid::from_str("@@.\n.@.\n..@").unwrap();
+    let centre = Position::at(1, 1);
+
+    assert_eq!(
+        [UP, LEFT, RIGHT, DOWN].map(|d| centre + d).to_vec(),
+        grid.neighbours(centre, Neighbourhood::Four)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        3,
+        grid.neighbours(Position::at(0, 0), Neighbourhood::Eight)
+            .count()
+    );
+    assert_eq!(
+        3,
+        grid.count_neighbours(centre, Neighbourhood::Eight, |c| c == '@')
+    );
+
+    // Custom offsets needn't be symmetric about the cell, so counting them checks they are
+    // applied in the right direction.
+    const OFFSETS: [Direction; 2] = [Direction::of(1, 2), Direction::of(2, 2)];
+    let custom = Neighbourhood::Custom(&OFFSETS);
+    assert_eq!(
+        1,
+        grid.count_neighbours(Position::at(0, 0), custom, |c| c == '@')
+    );
+
+    for neighbourhood in [Neighbourhood::Four, Neighbourhood::Eight, custom] {
+        let counts = grid.neighbour_counts(neighbourhood, |c| c == '@');
+        for pos in grid.positions() {
+            let count = grid.count_neighbours(pos, neighbourhood, |c| c == '@');
+            assert_eq!(count, counts.at(pos) as usize, "{neighbourhood:?} at {pos}");
+        }
+    }
+}
+
+#[test]
+fn test_wrapping() {
+    let grid = CharGrid::from_str("abc\ndef").unwrap().wrapping();
+    let corner = Position::at(0, 0);
+
+    assert_eq!(Some(Position::at(2, 1)), grid.step(corner, UP + LEFT));
+    assert_eq!(Position::at(1, 0), grid.wrap(Position::at(-5, 4)));
+    assert_eq!(
+        vec![
+            Position::at(0, 1),
+            Position::at(2, 0),
+            Position::at(1, 0),
+            Position::at(0, 1)
+        ],
+        grid.neighbours(corner, Neighbourhood::Four)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        8,
+        grid.count_neighbours(Position::at(1, 0), Neighbourhood::Eight, |_| true)
+    );
+
+    // Lines go round once, diagonals visiting every cell they can reach.
+    assert_eq!(vec!['b', 'a', 'c'], grid.line(Position::at(1, 0), LEFT));
+    assert_eq!(
+        vec!['a', 'e', 'c', 'd', 'b', 'f'],
+        grid.line(corner, RIGHT + DOWN)
+    );
+
+    let counts = grid.neighbour_counts(Neighbourhood::Four, |c| c == 'a');
+    assert_eq!(2, counts.at(Position::at(0, 1)));
+    assert_eq!(1, counts.at(Position::at(2, 0)));
+}
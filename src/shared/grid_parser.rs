@@ -0,0 +1,183 @@
+use crate::shared::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    // Each byte is a cell. Fast, but the input must be ASCII.
+    Bytes,
+    // Each char is a cell, however many bytes it takes.
+    Unicode,
+}
+
+// Parses lines of text into a grid, one cell per byte or per char. Lines must all be the same
+// width, unless padding is set, in which case short lines are padded out to the longest.
+//
+//     let grid: ByteGrid = GridParser::new(Encoding::Bytes)
+//         .pad(' ')
+//         .parse(input, |c| c as u8)?;
+#[derive(Debug, Clone)]
+pub struct GridParser {
+    encoding: Encoding,
+    pad: Option<char>,
+    align_right: bool,
+}
+
+impl GridParser {
+    pub fn new(encoding: Encoding) -> GridParser {
+        GridParser {
+            encoding,
+            pad: None,
+            align_right: false,
+        }
+    }
+
+    // Pads short lines with a fill char, rather than failing. Useful for column-oriented inputs
+    // where an editor may have stripped trailing spaces.
+    pub fn pad(self, fill: char) -> GridParser {
+        GridParser {
+            pad: Some(fill),
+            ..self
+        }
+    }
+
+    // Pads short lines on the left instead of the right.
+    pub fn align_right(self) -> GridParser {
+        GridParser {
+            align_right: true,
+            ..self
+        }
+    }
+
+    // Splits each line into cells, padding short lines if set.
+    fn rows<C: Copy>(
+        &self,
+        str: &str,
+        fill: Option<C>,
+        cells: impl Fn(usize, &str) -> Result<Vec<C>>,
+    ) -> Result<Vec<Vec<C>>> {
+        let mut rows: Vec<Vec<C>> = Vec::new();
+
+        for (idx, line) in str.lines().enumerate() {
+            let row = cells(idx + 1, line)?;
+            if let Some(first) = rows.first().map(Vec::len)
+                && row.len() != first
+                && fill.is_none()
+            {
+                return err(&format!(
+                    "line {}: expected width {first}, found {}",
+                    idx + 1,
+                    row.len()
+                ));
+            }
+            rows.push(row);
+        }
+
+        if let Some(fill) = fill {
+            let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+            for row in &mut rows {
+                let padding = std::iter::repeat_n(fill, width - row.len());
+                if self.align_right {
+                    row.splice(0..0, padding);
+                } else {
+                    row.extend(padding);
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    // The bytes of a line, which must all be ASCII to be one cell each.
+    fn bytes(n: usize, line: &str) -> Result<Vec<u8>> {
+        match line.bytes().position(|b| !b.is_ascii()) {
+            None => Ok(line.bytes().collect()),
+            Some(col) => {
+                let c = line[col..].chars().next().unwrap_or_default();
+                err(&format!(
+                    "line {n}, column {}: non-ASCII character '{c}' in a byte grid",
+                    col + 1
+                ))
+            }
+        }
+    }
+
+    pub fn parse<T, S: Storage<T>>(
+        &self,
+        str: &str,
+        cell: impl Fn(char) -> T,
+    ) -> Result<Grid<T, S>> {
+        match self.encoding {
+            Encoding::Unicode => {
+                let rows = self.rows(str, self.pad, |_, line| Ok(line.chars().collect()))?;
+                Grid::from_rows(rows.into_iter().map(|row| row.into_iter().map(&cell)))
+            }
+            Encoding::Bytes => {
+                let fill = match self.pad {
+                    Some(fill) if !fill.is_ascii() => {
+                        return err(&format!("cannot pad a byte grid with non-ASCII '{fill}'"));
+                    }
+                    fill => fill.map(|c| c as u8),
+                };
+                let rows = self.rows(str, fill, GridParser::bytes)?;
+                Grid::from_rows(
+                    rows.into_iter()
+                        .map(|row| row.into_iter().map(|b| cell(char::from(b)))),
+                )
+            }
+        }
+    }
+}
+
+#[test]
+fn test_unicode() {
+    let grid: CharGrid = GridParser::new(Encoding::Unicode)
+        .parse("aé\n→b", |c| c)
+        .unwrap();
+
+    assert_eq!((2, 2), (grid.width(), grid.height()));
+    assert_eq!('é', grid.at_xy(1, 0));
+    assert_eq!('→', grid.at_xy(0, 1));
+
+    let ragged: Result<CharGrid> = GridParser::new(Encoding::Unicode).parse("ab\nab\nabc", |c| c);
+    assert_eq!(Err(Error::new("line 3: expected width 2, found 3")), ragged);
+}
+
+#[test]
+fn test_bytes() {
+    let grid: ByteGrid = GridParser::new(Encoding::Bytes)
+        .parse("#.\n.#", |c| c as u8)
+        .unwrap();
+    assert_eq!(b'#', grid.at_xy(1, 1));
+
+    let unicode: Result<ByteGrid> = GridParser::new(Encoding::Bytes).parse("ab\naé", |c| c as u8);
+    assert_eq!(
+        Err(Error::new(
+            "line 2, column 2: non-ASCII character 'é' in a byte grid"
+        )),
+        unicode
+    );
+}
+
+#[test]
+fn test_pad() {
+    let parser = GridParser::new(Encoding::Bytes).pad('.');
+
+    let grid: CharGrid = parser.parse("ab\na\n\nabc", |c| c).unwrap();
+    assert_eq!("ab.\na..\n...\nabc\n\n", grid.draw());
+
+    let grid: CharGrid = parser.align_right().parse("ab\nabc", |c| c).unwrap();
+    assert_eq!(".ab\nabc\n\n", grid.draw());
+
+    let unicode: Result<ByteGrid> = GridParser::new(Encoding::Bytes)
+        .pad('→')
+        .parse("ab\na", |c| c as u8);
+    assert_eq!(
+        Err(Error::new("cannot pad a byte grid with non-ASCII '→'")),
+        unicode
+    );
+
+    let grid: CharGrid = GridParser::new(Encoding::Unicode)
+        .pad('→')
+        .parse("ab\na", |c| c)
+        .unwrap();
+    assert_eq!('→', grid.at_xy(1, 1));
+}
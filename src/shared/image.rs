@@ -0,0 +1,452 @@
+use crate::shared::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub type Rgb = [u8; 3];
+
+// Colours for the chars of a grid. Chars without a colour of their own get one picked from a
+// fixed set by their code, so that any grid can be exported without setting up a palette.
+#[derive(Debug, Clone, Default)]
+pub struct Palette {
+    colors: HashMap<char, Rgb>,
+}
+
+const FALLBACK: [Rgb; 8] = [
+    [230, 25, 75],
+    [60, 180, 75],
+    [255, 225, 25],
+    [0, 130, 200],
+    [245, 130, 48],
+    [145, 30, 180],
+    [70, 240, 240],
+    [240, 50, 230],
+];
+
+impl Palette {
+    pub fn new() -> Palette {
+        Palette::default()
+            .color('.', [16, 16, 16])
+            .color(' ', [0, 0, 0])
+            .color('#', [200, 200, 200])
+            .color('@', [255, 255, 255])
+    }
+
+    pub fn color(mut self, c: char, rgb: Rgb) -> Palette {
+        self.colors.insert(c, rgb);
+        self
+    }
+
+    pub fn get(&self, c: char) -> Rgb {
+        match self.colors.get(&c) {
+            Some(rgb) => *rgb,
+            None => FALLBACK[c as usize % FALLBACK.len()],
+        }
+    }
+}
+
+// An RGB image of a grid, with each cell drawn as a scale x scale block of pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn of(grid: &CharGrid, palette: &Palette, scale: usize) -> Image {
+        let width = grid.width() as usize * scale;
+        let height = grid.height() as usize * scale;
+
+        let mut pixels = Vec::with_capacity(width * height);
+        for py in 0..height {
+            for px in 0..width {
+                let c = grid.at_xy((px / scale) as i32, (py / scale) as i32);
+                pixels.push(palette.get(c));
+            }
+        }
+
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    // A binary PPM (P6), the simplest format most image tools can read.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend(self.pixels.iter().flatten());
+        out
+    }
+
+    // A PNG with its image data stored uncompressed, which keeps the encoder small at the cost of
+    // larger files. PNGs can't be empty, so an image without any pixels is an error.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        if self.width == 0 || self.height == 0 {
+            return err("cannot export an empty image as a PNG");
+        }
+
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width) {
+            // Each scanline starts with its filter type, which is always none.
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filtering and no interlacing.
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut out, b"IHDR", &header);
+        png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut out, b"IEND", &[]);
+        Ok(out)
+    }
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffffffffu32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// Wraps data in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+
+    let blocks: Vec<&[u8]> = data.chunks(0xffff).collect();
+    if blocks.is_empty() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        let last = i == blocks.len() - 1;
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(*block);
+    }
+
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+// Packs variable width codes into bytes, least significant bit first, as GIF expects.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u32) {
+        self.acc |= (code as u32) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.acc as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.acc as u8);
+        }
+        self.bytes
+    }
+}
+
+// Compresses colour indices with GIF's variant of LZW, starting over with a clear code whenever
+// the table of 4096 codes fills up.
+fn lzw(indices: &[u8], min_width: u32) -> Vec<u8> {
+    let clear = 1u16 << min_width;
+    let end = clear + 1;
+
+    let mut out = BitWriter::default();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut width = min_width + 1;
+
+    out.write(clear, width);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        out.write(end, width);
+        return out.finish();
+    };
+
+    let mut prefix = first as u16;
+    for &index in rest {
+        if let Some(&code) = table.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        out.write(prefix, width);
+        if next < 4096 {
+            table.insert((prefix, index), next);
+            next += 1;
+            if next > 1 << width && width < 12 {
+                width += 1;
+            }
+        } else {
+            out.write(clear, width);
+            table.clear();
+            next = end + 1;
+            width = min_width + 1;
+        }
+        prefix = index as u16;
+    }
+
+    out.write(prefix, width);
+    out.write(end, width);
+    out.finish()
+}
+
+// Encodes frames as an animated GIF, looping forever, showing each frame for delay hundredths of
+// a second. Frames must all be the same size, and use no more than 256 colours between them.
+pub fn to_gif(frames: &[Image], delay: u16) -> Result<Vec<u8>> {
+    let Some(first) = frames.first() else {
+        return err("no frames to export");
+    };
+    if frames
+        .iter()
+        .any(|f| f.width != first.width || f.height != first.height)
+    {
+        return err("frames are not all the same size");
+    }
+    // GIFs store sizes in 16 bits.
+    let (Ok(width), Ok(height)) = (u16::try_from(first.width), u16::try_from(first.height)) else {
+        return err(&format!(
+            "{}x{} is too large for a GIF, which is at most {}x{}",
+            first.width,
+            first.height,
+            u16::MAX,
+            u16::MAX
+        ));
+    };
+
+    let mut colors: Vec<Rgb> = Vec::new();
+    let mut lookup: HashMap<Rgb, u8> = HashMap::new();
+    for rgb in frames.iter().flat_map(|f| f.pixels.iter()) {
+        if !lookup.contains_key(rgb) {
+            if colors.len() == 256 {
+                return err("frames use more than 256 colours");
+            }
+            lookup.insert(*rgb, colors.len() as u8);
+            colors.push(*rgb);
+        }
+    }
+
+    // The colour table must have a power of two entries, and LZW codes need at least 2 bits.
+    let bits = (colors.len().max(2).next_power_of_two().trailing_zeros()).max(1);
+    colors.resize(1 << bits, [0, 0, 0]);
+    let min_width = bits.max(2);
+
+    let mut out = b"GIF89a".to_vec();
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    out.extend([0x80 | (bits as u8 - 1), 0, 0]);
+    out.extend(colors.iter().flatten());
+
+    // Loop forever.
+    out.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for frame in frames {
+        out.extend([0x21, 0xf9, 0x04, 0x00]);
+        out.extend(delay.to_le_bytes());
+        out.extend([0x00, 0x00]);
+
+        out.push(0x2c);
+        out.extend([0, 0, 0, 0]);
+        out.extend(width.to_le_bytes());
+        out.extend(height.to_le_bytes());
+        out.push(0);
+
+        let indices: Vec<u8> = frame.pixels.iter().map(|rgb| lookup[rgb]).collect();
+        out.push(min_width as u8);
+        for block in lzw(&indices, min_width).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend(block);
+        }
+        out.push(0);
+    }
+
+    out.push(0x3b);
+    Ok(out)
+}
+
+// Writes frames to an image file, picking the format from its extension. A GIF animates every
+// frame; the other formats only show the last.
+pub fn export(path: &Path, frames: &[CharGrid], palette: &Palette, scale: usize) -> Result<()> {
+    let Some(last) = frames.last() else {
+        return err("no frames to export");
+    };
+
+    let bytes = match path.extension().and_then(|e| e.to_str()) {
+        Some("ppm") => Image::of(last, palette, scale).to_ppm(),
+        Some("png") => Image::of(last, palette, scale).to_png()?,
+        Some("gif") => {
+            let images: Vec<Image> = frames
+                .iter()
+                .map(|f| Image::of(f, palette, scale))
+                .collect();
+            to_gif(&images, 20)?
+        }
+        _ => {
+            return err(&format!(
+                "{}: expected a .ppm, .png or .gif file",
+                path.display()
+            ));
+        }
+    };
+
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+#[test]
+fn test_image_of() {
+    let cg = CharGrid::from_str("#.").unwrap();
+    let palette = Palette::new().color('#', [1, 2, 3]).color('.', [4, 5, 6]);
+    let image = Image::of(&cg, &palette, 2);
+
+    assert_eq!((4, 2), (image.width, image.height));
+    assert_eq!(
+        vec![[1, 2, 3], [1, 2, 3], [4, 5, 6], [4, 5, 6]],
+        image.pixels[4..]
+    );
+    assert_eq!(b"P6\n4 2\n255\n\x01\x02\x03", &image.to_ppm()[..14]);
+}
+
+#[test]
+fn test_png() {
+    let image = Image::of(&CharGrid::from_str("#.").unwrap(), &Palette::new(), 1);
+    let png = image.to_png().unwrap();
+    assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+    assert_eq!([0, 0, 0, 2, 0, 0, 0, 1], png[16..24]);
+
+    let empty = Image::of(&CharGrid::from_str("#.").unwrap(), &Palette::new(), 0);
+    assert!(empty.to_png().is_err());
+}
+
+#[test]
+fn test_checksums() {
+    assert_eq!(0xcbf43926, crc32(b"123456789"));
+    assert_eq!(0x091e01de, adler32(b"123456789"));
+    assert_eq!(
+        vec![
+            0x78, 0x01, 1, 3, 0, 0xfc, 0xff, b'a', b'b', b'c', 0x02, 0x4d, 0x01, 0x27
+        ],
+        zlib_stored(b"abc")
+    );
+}
+
+#[test]
+fn test_lzw() {
+    // Decodes codes written by lzw, growing the code width as the decoder in a GIF viewer would.
+    fn decode(bytes: &[u8], min_width: u32) -> Vec<u8> {
+        let clear = 1usize << min_width;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut width = min_width + 1;
+        let mut prev: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+
+        let (mut acc, mut bits, mut bytes) = (0u32, 0u32, bytes.iter());
+        loop {
+            while bits < width {
+                acc |= (*bytes.next().unwrap() as u32) << bits;
+                bits += 8;
+            }
+            let code = (acc & ((1 << width) - 1)) as usize;
+            acc >>= width;
+            bits -= width;
+
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).collect();
+                table.extend([vec![], vec![]]);
+                width = min_width + 1;
+                prev = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return out;
+            }
+
+            let entry = match (table.get(code), &prev) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(p)) => [p.clone(), vec![p[0]]].concat(),
+                (None, None) => panic!("unknown code {code}"),
+            };
+            if let Some(p) = prev
+                && table.len() < 4096
+            {
+                table.push([p, vec![entry[0]]].concat());
+                if table.len() == 1 << width && width < 12 {
+                    width += 1;
+                }
+            }
+            out.extend(&entry);
+            prev = Some(entry);
+        }
+    }
+
+    let pattern: Vec<u8> = (0..20000u32).map(|i| ((i * i / 7) % 4) as u8).collect();
+    assert_eq!(pattern, decode(&lzw(&pattern, 2), 2));
+
+    let runs: Vec<u8> = (0..5000u32).map(|i| (i / 300 % 2) as u8).collect();
+    assert_eq!(runs, decode(&lzw(&runs, 2), 2));
+
+    assert_eq!(Vec::<u8>::new(), decode(&lzw(&[], 2), 2));
+}
+
+#[test]
+fn test_gif() {
+    let a = Image::of(&CharGrid::from_str("#.").unwrap(), &Palette::new(), 1);
+    let b = Image::of(&CharGrid::from_str("..").unwrap(), &Palette::new(), 1);
+
+    let gif = to_gif(&[a.clone(), b], 10).unwrap();
+    assert_eq!(b"GIF89a\x02\x00\x01\x00\x80", &gif[..11]);
+    assert_eq!(Some(&0x3b), gif.last());
+
+    let c = Image::of(&CharGrid::from_str(".").unwrap(), &Palette::new(), 1);
+    assert!(to_gif(&[a, c], 10).is_err());
+
+    let wide = Image {
+        width: 70000,
+        height: 1,
+        pixels: vec![[0, 0, 0]; 70000],
+    };
+    assert!(to_gif(&[wide], 10).is_err());
+}
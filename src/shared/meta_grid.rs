@@ -1,29 +1,45 @@
 use crate::shared::*;
-use std::collections::HashSet;
 use std::fmt::Display;
 use std::fs::read_to_string;
 
-pub trait Metadata: Default + Clone + Display {}
-impl<T: Default + Clone + Display> Metadata for T {}
+pub trait Metadata: Default + Clone {}
+impl<T: Default + Clone> Metadata for T {}
 
 // A 2d grid of chars which can be loaded from a text file,
 // and carries metadata about each cell.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MetaGrid<M: Metadata> {
-    width: i32,
-    chars: Vec<char>,
-    meta: Vec<M>,
+    chars: CharGrid,
+    meta: Grid<M>,
 }
 
+pub type MetaGridView<'a, M> = View<'a, MetaGrid<M>>;
+
 impl<M: Metadata> MetaGrid<M> {
     pub fn new(width: i32, height: i32) -> MetaGrid<M> {
         debug_assert!(width >= 1);
         debug_assert!(height >= 1);
 
-        let chars = vec![' '; (width * height) as usize];
-        let meta = vec![Default::default(); (width * height) as usize];
+        MetaGrid {
+            chars: CharGrid::blank(width, height),
+            meta: Grid::new(width, height),
+        }
+    }
+
+    // Attaches default metadata to every cell of a grid of chars.
+    pub fn of(chars: CharGrid) -> MetaGrid<M> {
+        let meta = Grid::new(chars.width(), chars.height());
+        MetaGrid::from_parts(chars, meta)
+    }
+
+    // Pairs a grid of chars with a grid of metadata of the same size. The result wraps if either
+    // part does.
+    pub fn from_parts(chars: CharGrid, meta: Grid<M>) -> MetaGrid<M> {
+        debug_assert!(chars.width() == meta.width() && chars.height() == meta.height());
 
-        MetaGrid { width, chars, meta }
+        let wrap = chars.wraps() || meta.wraps();
+        let grid = MetaGrid { chars, meta };
+        if wrap { grid.wrapping() } else { grid }
     }
 
     pub fn from_file(path: &str) -> Result<MetaGrid<M>> {
@@ -33,27 +49,27 @@ impl<M: Metadata> MetaGrid<M> {
     }
 
     pub fn from_str(str: &str) -> Result<MetaGrid<M>> {
-        let mut width: i32 = 0;
-        let mut chars = Vec::new();
-
-        for line in str.lines() {
-            let trimmed = line.trim_end_matches("\n");
-            if width == 0 {
-                width = trimmed.len() as i32
-            } else if width != trimmed.len() as i32 {
-                return Err(Error::new("lines do not have equal length"));
-            }
+        Ok(Self::of(CharGrid::from_str(str)?))
+    }
 
-            for c in trimmed.chars() {
-                chars.push(c);
-            }
+    // Makes the grid toroidal, so that neighbours and searches wrap around its edges.
+    pub fn wrapping(self) -> MetaGrid<M> {
+        MetaGrid {
+            chars: self.chars.wrapping(),
+            meta: self.meta.wrapping(),
         }
+    }
 
-        let meta = vec![Default::default(); chars.len()];
-        let new = MetaGrid { width, chars, meta };
-        debug_assert!(new.chars.len() == new.meta.len());
+    pub fn wraps(&self) -> bool {
+        self.chars.wraps()
+    }
+
+    pub fn chars(&self) -> &CharGrid {
+        &self.chars
+    }
 
-        Ok(new)
+    pub fn metas(&self) -> &Grid<M> {
+        &self.meta
     }
 
     pub fn fill(&mut self, c: char) {
@@ -61,48 +77,35 @@ impl<M: Metadata> MetaGrid<M> {
     }
 
     pub fn width(&self) -> i32 {
-        self.width
+        self.chars.width()
     }
 
     pub fn x_max(&self) -> i32 {
-        self.width - 1
+        self.chars.x_max()
     }
 
     pub fn height(&self) -> i32 {
-        self.chars.len() as i32 / self.width()
+        self.chars.height()
     }
 
     pub fn y_max(&self) -> i32 {
-        self.height() - 1
+        self.chars.y_max()
     }
 
     pub fn set(&mut self, pos: Position, c: char) {
-        debug_assert!(
-            self.in_bounds(pos),
-            "set called with out of bounds position {}",
-            pos
-        );
-
-        self.chars[((pos.y * self.width) + pos.x) as usize] = c
+        self.chars.set(pos, c)
     }
 
     pub fn set_checked(&mut self, pos: Position, c: char) -> bool {
-        if self.in_bounds(pos) {
-            self.set(pos, c);
-            return true;
-        }
-
-        return false;
+        self.chars.set_checked(pos, c)
     }
 
     pub fn at(&self, pos: Position) -> (char, &M) {
-        let idx = ((pos.y * self.width) + pos.x) as usize;
-        (self.chars[idx], &self.meta[idx])
+        (self.chars.at(pos), self.meta.get(pos))
     }
 
     pub fn at_owned(&self, pos: Position) -> (char, M) {
-        let idx = ((pos.y * self.width) + pos.x) as usize;
-        (self.chars[idx], self.meta[idx].clone())
+        (self.chars.at(pos), self.meta.at(pos))
     }
 
     pub fn at_checked(&self, pos: Position) -> Option<(char, &M)> {
@@ -114,133 +117,82 @@ impl<M: Metadata> MetaGrid<M> {
     }
 
     pub fn meta(&self, pos: Position) -> &M {
-        let idx = ((pos.y * self.width) + pos.x) as usize;
-        &self.meta[idx]
+        self.meta.get(pos)
     }
 
     pub fn set_meta(&mut self, pos: Position, v: M) {
-        let idx = ((pos.y * self.width) + pos.x) as usize;
-        self.meta[idx] = v;
+        self.meta.set(pos, v);
     }
 
     pub fn set_meta_checked(&mut self, pos: Position, v: M) {
-        if self.in_bounds(pos) {
-            self.set_meta(pos, v);
-        }
+        self.meta.set_checked(pos, v);
     }
 
     pub fn find_one(&self, c: char) -> Result<Position> {
-        let item = self.chars.iter().enumerate().find(|(idx, e)| **e == c);
-
-        match item {
-            Some((idx, _)) => Ok(Position::at(
-                idx as i32 % self.width,
-                idx as i32 / self.width,
-            )),
-            None => Err(Error::new(&format!("char '{}' not found in MetaGrid", c))),
-        }
+        self.chars.find_one(c)
     }
 
     pub fn find_all(&self, c: char) -> Vec<Position> {
+        self.chars.find_all(c)
+    }
+
+    // The in bounds neighbours of a position, with their chars and metadata.
+    pub fn neighbours(
+        &self,
+        pos: Position,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = (Position, char, &M)> + '_ {
         self.chars
-            .iter()
-            .enumerate()
-            .filter(|(idx, e)| **e == c)
-            .map(|(idx, _)| Position {
-                x: idx as i32 % self.width,
-                y: idx as i32 / self.width,
-            })
-            .collect()
+            .neighbours(pos, neighbourhood)
+            .map(|p| (p, self.chars.at(p), self.meta.get(p)))
     }
 
     pub fn adjacent(&self, pos: Position) -> Vec<(Position, char, M)> {
-        let mut items = Vec::new();
-
-        let positions = [
-            pos + UP + LEFT,
-            pos + UP,
-            pos + UP + RIGHT,
-            pos + LEFT,
-            // self
-            pos + RIGHT,
-            pos + DOWN + LEFT,
-            pos + DOWN,
-            pos + DOWN + RIGHT,
-        ];
-
-        for p in positions {
-            if self.in_bounds(p) {
-                let at = self.at(p);
-                items.push((p, at.0, at.1.clone()));
-            }
-        }
-
-        return items;
+        self.neighbours(pos, Neighbourhood::Eight)
+            .map(|(p, c, m)| (p, c, m.clone()))
+            .collect()
     }
 
     pub fn adjacent_orthogonal(&self, pos: Position) -> Vec<(Position, char, M)> {
-        let mut items = Vec::new();
-
-        for p in [pos + UP, pos + LEFT, pos + RIGHT, pos + DOWN] {
-            if self.in_bounds(p) {
-                let at = self.at(p);
-                items.push((p, at.0, at.1.clone()));
-            }
-        }
-
-        return items;
+        self.neighbours(pos, Neighbourhood::Four)
+            .map(|(p, c, m)| (p, c, m.clone()))
+            .collect()
     }
 
     pub fn in_bounds(&self, p: Position) -> bool {
-        p.x >= 0 && p.y >= 0 && p.x <= self.x_max() && p.y <= self.y_max()
+        self.chars.in_bounds(p)
     }
 
     pub fn count(&self, c: char) -> usize {
-        self.chars.iter().filter(|v| **v == c).count()
+        self.chars.count(c)
     }
 
     pub fn uniq_chars(&self) -> Vec<char> {
-        let mut chars = HashSet::new();
-
-        for c in &self.chars {
-            chars.insert(*c);
-        }
+        self.chars.uniq()
+    }
 
-        chars.into_iter().collect()
+    // The chars of the grid, without their metadata.
+    pub fn char_grid(&self) -> CharGrid {
+        self.chars.clone()
     }
 
-    pub fn windows(&self, width: i32, height: i32) -> Windows<'_, M> {
-        Windows {
-            cg: self,
-            x: 0,
-            y: 0,
-            width,
-            height,
-        }
+    pub fn windows(&self, width: i32, height: i32) -> Windows<'_, MetaGrid<M>> {
+        Windows::new(self, (self.width(), self.height()), (width, height))
     }
 
     pub fn draw(&self) -> String {
-        let mut str = String::new();
-
-        for y in 0..self.height() {
-            for x in 0..self.width {
-                str.push(self.at(Position::at(x, y)).0)
-            }
-            str.push('\n')
-        }
-        str.push('\n');
-
-        str
+        self.chars.draw()
     }
 
-    pub fn draw_meta(&self) -> String {
+    // Draws each cell's char followed by its metadata, as drawn by meta.
+    pub fn draw_meta_with(&self, meta: impl Fn(&M) -> String) -> String {
         let mut str = String::new();
 
         for y in 0..self.height() {
-            for x in 0..self.width {
+            for x in 0..self.width() {
                 let here = self.at(Position::at(x, y));
                 str.push(here.0);
-                str.push_str(&format!("({})", here.1));
+                str.push_str(&format!("({})", meta(here.1)));
                 if x != self.x_max() {
                     str.push(' ');
                 }
@@ -253,12 +205,24 @@ impl<M: Metadata> MetaGrid<M> {
     }
 }
 
+impl<M: Metadata + Display> MetaGrid<M> {
+    pub fn draw_meta(&self) -> String {
+        self.draw_meta_with(M::to_string)
+    }
+}
+
+impl<M: Metadata> AsRef<CharGrid> for MetaGrid<M> {
+    fn as_ref(&self) -> &CharGrid {
+        &self.chars
+    }
+}
+
 impl<T> MetaGrid<T>
 where
     T: std::iter::Sum + Metadata + Copy,
 {
     pub fn sum_meta(&self) -> T {
-        self.meta.iter().copied().sum()
+        self.meta.cells().iter().copied().sum()
     }
 }
 
@@ -267,8 +231,17 @@ where
     T: std::ops::AddAssign + Metadata,
 {
     pub fn inc_meta(&mut self, pos: Position, v: T) {
-        let idx = ((pos.y * self.width) + pos.x) as usize;
-        self.meta[idx] += v;
+        *self.meta.get_mut(pos) += v;
+    }
+}
+
+impl<M: Metadata> View<'_, MetaGrid<M>> {
+    pub fn at(&self, pos: Position) -> (char, &M) {
+        self.grid.at(pos + self.offset)
+    }
+
+    pub fn chars(&self) -> Vec<char> {
+        self.positions().map(|p| self.at(p).0).collect()
     }
 }
 
@@ -302,65 +275,15 @@ fn test_find_one() {
     assert!(mg.find_one('^').is_err());
 }
 
-// Windows is an iterator over a MetaGrid that yields every permutation of the given size as a
-// read-only view.
-pub struct Windows<'a, M: Metadata> {
-    cg: &'a MetaGrid<M>,
-    x: i32,
-    y: i32,
-    width: i32,
-    height: i32,
-}
-
-impl<'a, M: Metadata> Iterator for Windows<'a, M> {
-    type Item = MetaGridView<'a, M>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.x > self.cg.width() - self.width || self.y > self.cg.height() - self.height {
-            return None;
-        }
-
-        let result = Some(MetaGridView {
-            cg: self.cg,
-            x_offset: self.x,
-            y_offset: self.y,
-            width: self.width,
-            height: self.height,
-        });
-
-        self.x += 1;
-        if self.x > self.cg.width() - self.width {
-            self.x = 0;
-            self.y += 1;
-        }
-
-        result
-    }
-}
-
-#[derive(Debug)]
-pub struct MetaGridView<'a, M: Metadata> {
-    cg: &'a MetaGrid<M>,
-    x_offset: i32,
-    y_offset: i32,
-    width: i32,
-    height: i32,
-}
-
-impl<M: Metadata> MetaGridView<'_, M> {
-    pub fn at(&self, pos: Position) -> (char, &M) {
-        self.cg.at(pos + Position::at(self.x_offset, self.y_offset))
-    }
-
-    pub fn chars(&self) -> Vec<char> {
-        let mut v = Vec::new();
+#[test]
+fn test_meta() {
+    let mut mg: MetaGrid<usize> = MetaGrid::from_str("ab\ncd").unwrap();
 
-        for y in 0..self.height {
-            for x in 0..self.width {
-                v.push(self.at(Position::at(x, y)).0)
-            }
-        }
+    mg.set_meta(Position::at(1, 0), 3);
+    mg.inc_meta(Position::at(1, 0), 2);
+    mg.inc_meta(Position::at(0, 1), 1);
 
-        v
-    }
+    assert_eq!(('b', &5), mg.at(Position::at(1, 0)));
+    assert_eq!(6, mg.sum_meta());
+    assert_eq!("a(0) b(5)\nc(1) d(0)\n\n", mg.draw_meta());
 }
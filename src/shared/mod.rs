@@ -1,12 +1,42 @@
 #![allow(unused)]
+mod grid;
+pub use grid::{BitGrid, Bits, ByteGrid, Grid, GridView, Neighbourhood, Storage, View, Windows};
+mod grid_parser;
+pub use grid_parser::{Encoding, GridParser};
 mod char_grid;
 pub use char_grid::{CharGrid, CharGridView};
 mod meta_grid;
 pub use meta_grid::{MetaGrid, MetaGridView, Metadata};
+mod sparse_grid;
+pub use sparse_grid::SparseGrid;
+
+mod pathfinding;
+pub use pathfinding::{Paths, Search, chebyshev, manhattan};
+
+mod regions;
+pub use regions::{Components, Region, flood_fill, label};
+
+mod transform;
+
+mod automaton;
+pub use automaton::Automaton;
+
+mod cycles;
+pub use cycles::{Cycle, find_cycle, state_after, state_and_cycle_after};
+
+mod union_find;
+pub use union_find::UnionFind;
 
 mod readers;
 pub use readers::*;
 
+pub mod image;
+
+mod render;
+pub use render::{Color, Heat, Renderer, Style};
+
+pub mod visualize;
+
 use std::fmt::Formatter;
 use std::num::{ParseFloatError, ParseIntError};
 use std::ops::Add;
@@ -47,7 +77,7 @@ impl std::error::Error for Error {}
 impl From<ParseIntError> for Error {
     fn from(value: ParseIntError) -> Self {
         Error {
-            msg: format!("failed to parse int: {}", value.to_string()),
+            msg: format!("failed to parse int: {}", value),
         }
     }
 }
@@ -55,7 +85,7 @@ impl From<ParseIntError> for Error {
 impl From<ParseFloatError> for Error {
     fn from(value: ParseFloatError) -> Self {
         Error {
-            msg: format!("failed to parse float: {}", value.to_string()),
+            msg: format!("failed to parse float: {}", value),
         }
     }
 }
@@ -63,7 +93,7 @@ impl From<ParseFloatError> for Error {
 impl From<std::io::Error> for Error {
     fn from(value: std::io::Error) -> Self {
         Error {
-            msg: format!("i/o error: {}", value.to_string()),
+            msg: format!("i/o error: {}", value),
         }
     }
 }
@@ -71,7 +101,7 @@ impl From<std::io::Error> for Error {
 impl From<regex::Error> for Error {
     fn from(value: regex::Error) -> Self {
         Error {
-            msg: format!("regex error: {}", value.to_string()),
+            msg: format!("regex error: {}", value),
         }
     }
 }
@@ -82,9 +112,10 @@ impl From<String> for Error {
     }
 }
 
+// A day's puzzle. Each part is given the puzzle input, and returns the answer.
 pub trait Solution {
-    fn part1(&self) -> Result<String>;
-    fn part2(&self) -> Result<String>;
+    fn part1(&self, input: &str) -> Result<String>;
+    fn part2(&self, input: &str) -> Result<String>;
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -96,7 +127,7 @@ pub const UP: Direction = Direction(0, -1);
 pub const DOWN: Direction = Direction(0, 1);
 
 impl Direction {
-    pub fn of(x: i8, y: i8) -> Direction {
+    pub const fn of(x: i8, y: i8) -> Direction {
         Direction(x, y)
     }
 
@@ -220,23 +251,16 @@ impl Position {
         rhs: Direction,
         bounds: (Position, Dimensions),
     ) -> Position {
-        let mut pos = self + rhs;
-
-        while pos.x < bounds.0.x {
-            pos.x += bounds.1.w;
-        }
-        while pos.y < bounds.0.y {
-            pos.y += bounds.1.h;
-        }
+        self.wrapping_add_pos(Position::at(rhs.0 as i32, rhs.1 as i32), bounds)
+    }
 
-        while pos.x >= bounds.0.x + bounds.1.w {
-            pos.x -= bounds.1.w;
-        }
-        while pos.y >= bounds.0.y + bounds.1.h {
-            pos.y -= bounds.1.h;
+    // Adds an offset of any size, wrapping around the edges of the bounds as often as needed.
+    pub fn wrapping_add_pos(&self, rhs: Position, bounds: (Position, Dimensions)) -> Position {
+        let (min, dims) = bounds;
+        Position {
+            x: min.x + (self.x + rhs.x - min.x).rem_euclid(dims.w),
+            y: min.y + (self.y + rhs.y - min.y).rem_euclid(dims.h),
         }
-
-        pos
     }
 }
 
@@ -270,11 +294,16 @@ fn test_wrapping_add_direction() {
 
     pos = pos.wrapping_add_direction(Direction::of(1, 2), bounds);
     assert_eq!(Position::at(2, 2), pos);
+
+    // Bounds needn't start at the origin, and offsets can go round many times.
+    let bounds = (Position::at(-3, 5), Dimensions::of(4, 3));
+    let pos = Position::at(-3, 5).wrapping_add_pos(Position::at(-1_000_001, 3_000_002), bounds);
+    assert_eq!(Position::at(0, 7), pos);
 }
 
 pub fn permutations<T: Clone>(n: usize, items: &[T]) -> Vec<Vec<T>> {
-    fn append<T: Clone>(v: &Vec<T>, op: T) -> Vec<T> {
-        let mut v = v.clone();
+    fn append<T: Clone>(v: &[T], op: T) -> Vec<T> {
+        let mut v = v.to_vec();
         v.push(op);
         v
     }
@@ -401,7 +430,7 @@ impl Digits {
 
         for pos in (0..d.len).rev() {
             d.ds[pos as usize] = (n % 10) as u8;
-            n = n / 10;
+            n /= 10;
         }
 
         return d;
@@ -438,11 +467,11 @@ fn test_digits_of() {
 
     for (n, ref expected) in test_cases {
         let d = Digits::of(n);
-        for i in 0..expected.len() {
+        for (i, digit) in expected.iter().enumerate() {
             assert_eq!(
-                expected[i], d.ds[i] as usize,
+                *digit, d.ds[i] as usize,
                 "digit at index {i} should be {}, but was {}",
-                expected[i], d.ds[i]
+                digit, d.ds[i]
             );
         }
     }
@@ -453,7 +482,7 @@ pub fn split_docs(s: String) -> Vec<String> {
     let mut docs = Vec::new();
 
     for line in s.lines() {
-        if line != "" {
+        if !line.is_empty() {
             buf.push_str(line);
             buf.push('\n');
         } else {
@@ -461,7 +490,7 @@ pub fn split_docs(s: String) -> Vec<String> {
             buf.clear();
         }
     }
-    if buf != "" {
+    if !buf.is_empty() {
         docs.push(buf);
     }
 
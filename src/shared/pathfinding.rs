@@ -0,0 +1,290 @@
+use crate::shared::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+
+pub fn manhattan(a: Position, b: Position) -> usize {
+    (a.x.abs_diff(b.x) + a.y.abs_diff(b.y)) as usize
+}
+
+pub fn chebyshev(a: Position, b: Position) -> usize {
+    a.x.abs_diff(b.x).max(a.y.abs_diff(b.y)) as usize
+}
+
+// A shortest path search over a grid, from any of a set of starts to the nearest of a set of
+// goals. With no goals, every reachable cell is explored.
+//
+//     let paths = Search::new(&grid)
+//         .start(grid.find_one('S')?)
+//         .goal(grid.find_one('E')?)
+//         .bfs(|_, to| grid.at(to) != '#');
+//     let steps = paths.goal_distance();
+pub struct Search<'a> {
+    grid: &'a CharGrid,
+    neighbourhood: Neighbourhood,
+    starts: Vec<Position>,
+    goals: HashSet<Position>,
+}
+
+// The result of a search: how far each cell is from the nearest start, and how to get there.
+#[derive(Debug, Clone)]
+pub struct Paths {
+    pub distances: MetaGrid<Option<usize>>,
+    previous: Grid<Option<Position>>,
+    // The first goal reached, if any.
+    pub goal: Option<Position>,
+}
+
+impl<'a> Search<'a> {
+    pub fn new(grid: &'a impl AsRef<CharGrid>) -> Search<'a> {
+        Search {
+            grid: grid.as_ref(),
+            neighbourhood: Neighbourhood::Four,
+            starts: Vec::new(),
+            goals: HashSet::new(),
+        }
+    }
+
+    pub fn neighbourhood(self, neighbourhood: Neighbourhood) -> Search<'a> {
+        Search {
+            neighbourhood,
+            ..self
+        }
+    }
+
+    pub fn start(mut self, pos: Position) -> Search<'a> {
+        self.starts.push(pos);
+        self
+    }
+
+    pub fn starts(mut self, positions: impl IntoIterator<Item = Position>) -> Search<'a> {
+        self.starts.extend(positions);
+        self
+    }
+
+    pub fn goal(mut self, pos: Position) -> Search<'a> {
+        self.goals.insert(pos);
+        self
+    }
+
+    pub fn goals(mut self, positions: impl IntoIterator<Item = Position>) -> Search<'a> {
+        self.goals.extend(positions);
+        self
+    }
+
+    fn paths(&self) -> Paths {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        Paths {
+            distances: MetaGrid::from_parts(self.grid.clone(), Grid::new(width, height)),
+            previous: Grid::new(width, height),
+            goal: None,
+        }
+    }
+
+    fn neighbours(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
+        self.grid.neighbours(pos, self.neighbourhood)
+    }
+
+    // Breadth first search, where every step costs 1. passable(from, to) says whether a step can
+    // be taken.
+    pub fn bfs(&self, passable: impl Fn(Position, Position) -> bool) -> Paths {
+        let mut paths = self.paths();
+        let mut queue = VecDeque::new();
+
+        for &start in &self.starts {
+            if paths.distance(start).is_none() {
+                paths.distances.set_meta(start, Some(0));
+                queue.push_back(start);
+            }
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            if self.goals.contains(&pos) {
+                paths.goal = Some(pos);
+                break;
+            }
+
+            let dist = paths.distance(pos).unwrap_or_default();
+            for next in self.neighbours(pos) {
+                if paths.distance(next).is_none() && passable(pos, next) {
+                    paths.distances.set_meta(next, Some(dist + 1));
+                    paths.previous.set(next, Some(pos));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        paths
+    }
+
+    // Dijkstra's algorithm. cost(from, to) is the cost of a step, or None if it can't be taken.
+    pub fn dijkstra(&self, cost: impl Fn(Position, Position) -> Option<usize>) -> Paths {
+        self.astar(cost, |_| 0)
+    }
+
+    // A* search, guided by a heuristic estimate of the cost from a position to the nearest goal.
+    // The heuristic must never overestimate for the paths found to be shortest.
+    pub fn astar(
+        &self,
+        cost: impl Fn(Position, Position) -> Option<usize>,
+        heuristic: impl Fn(Position) -> usize,
+    ) -> Paths {
+        let mut paths = self.paths();
+        let mut heap = BinaryHeap::new();
+        let idx = |p: Position| (p.y * self.grid.width() + p.x) as usize;
+        let pos =
+            |i: usize| Position::at(i as i32 % self.grid.width(), i as i32 / self.grid.width());
+
+        for &start in &self.starts {
+            paths.distances.set_meta(start, Some(0));
+            heap.push(Reverse((heuristic(start), 0, idx(start))));
+        }
+
+        while let Some(Reverse((_, dist, i))) = heap.pop() {
+            let here = pos(i);
+            if paths.distance(here).is_some_and(|d| d < dist) {
+                continue;
+            }
+            if self.goals.contains(&here) {
+                paths.goal = Some(here);
+                break;
+            }
+
+            for next in self.neighbours(here) {
+                let Some(step) = cost(here, next) else {
+                    continue;
+                };
+                let next_dist = dist + step;
+                if paths.distance(next).is_none_or(|d| next_dist < d) {
+                    paths.distances.set_meta(next, Some(next_dist));
+                    paths.previous.set(next, Some(here));
+                    heap.push(Reverse((next_dist + heuristic(next), next_dist, idx(next))));
+                }
+            }
+        }
+
+        paths
+    }
+}
+
+impl Paths {
+    pub fn distance(&self, pos: Position) -> Option<usize> {
+        *self.distances.meta(pos)
+    }
+
+    pub fn goal_distance(&self) -> Option<usize> {
+        self.goal.and_then(|g| self.distance(g))
+    }
+
+    // The path from the nearest start to a position, including both ends.
+    pub fn path_to(&self, pos: Position) -> Option<Vec<Position>> {
+        self.distance(pos)?;
+
+        let mut path = vec![pos];
+        while let Some(prev) = self.previous.at(*path.last()?) {
+            path.push(prev);
+        }
+        path.reverse();
+
+        Some(path)
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<Position>> {
+        self.path_to(self.goal?)
+    }
+}
+
+#[cfg(test)]
+const MAZE: &str = "\
+S.#.....
+.##.###.
+....#...
+.##.#.#E";
+
+#[test]
+fn test_bfs() {
+    let grid = CharGrid::from_str(MAZE).unwrap();
+    let start = grid.find_one('S').unwrap();
+    let end = grid.find_one('E').unwrap();
+
+    let paths = Search::new(&grid)
+        .start(start)
+        .goal(end)
+        .bfs(|_, to| grid.at(to) != '#');
+    assert_eq!(Some(end), paths.goal);
+    assert_eq!(Some(14), paths.goal_distance());
+
+    let path = paths.goal_path().unwrap();
+    assert_eq!(15, path.len());
+    assert_eq!((start, end), (path[0], path[14]));
+    assert!(path.windows(2).all(|w| manhattan(w[0], w[1]) == 1));
+
+    let diagonal = Search::new(&grid)
+        .neighbourhood(Neighbourhood::Eight)
+        .start(start)
+        .goal(end)
+        .bfs(|_, to| grid.at(to) != '#');
+    assert_eq!(Some(10), diagonal.goal_distance());
+
+    // With no goal, everything reachable is explored.
+    let everywhere = Search::new(&grid)
+        .start(start)
+        .bfs(|_, to| grid.at(to) != '#');
+    assert_eq!(None, everywhere.goal);
+    assert_eq!(Some(11), everywhere.distance(Position::at(7, 0)));
+    assert_eq!(None, everywhere.distance(Position::at(2, 0)));
+}
+
+#[test]
+fn test_multiple_starts() {
+    let grid = CharGrid::from_str("a....b").unwrap();
+
+    let paths = Search::new(&grid)
+        .starts(grid.find_all('a').into_iter().chain(grid.find_all('b')))
+        .bfs(|_, _| true);
+    let distances: Vec<_> = (0..6).map(|x| paths.distance(Position::at(x, 0))).collect();
+    assert_eq!(
+        vec![Some(0), Some(1), Some(2), Some(2), Some(1), Some(0)],
+        distances
+    );
+}
+
+#[test]
+fn test_dijkstra_and_astar() {
+    // Digits are the cost of stepping onto a cell.
+    let grid = CharGrid::from_str("1111\n9991\n1111").unwrap();
+    let start = Position::at(0, 0);
+    let end = Position::at(0, 2);
+    let cost = |_, to: Position| grid.at(to).to_digit(10).map(|d| d as usize);
+
+    let dijkstra = Search::new(&grid).start(start).goal(end).dijkstra(cost);
+    assert_eq!(Some(8), dijkstra.goal_distance());
+
+    let astar = Search::new(&grid)
+        .start(start)
+        .goal(end)
+        .astar(cost, |p| manhattan(p, end));
+    assert_eq!(Some(8), astar.goal_distance());
+    assert_eq!(dijkstra.goal_path(), astar.goal_path());
+    assert_eq!(Some(&Position::at(3, 1)), astar.goal_path().unwrap().get(4));
+}
+
+#[test]
+fn test_wrapping() {
+    // The shortest way round is off the left edge and back on the right.
+    let grid = CharGrid::from_str("S.#..E").unwrap();
+    let (start, end) = (grid.find_one('S').unwrap(), grid.find_one('E').unwrap());
+
+    let flat = Search::new(&grid)
+        .start(start)
+        .goal(end)
+        .bfs(|_, to| grid.at(to) != '#');
+    assert_eq!(None, flat.goal_distance());
+
+    let grid = grid.wrapping();
+    let paths = Search::new(&grid)
+        .start(start)
+        .goal(end)
+        .bfs(|_, to| grid.at(to) != '#');
+    assert_eq!(Some(1), paths.goal_distance());
+    assert_eq!(Some(vec![start, end]), paths.goal_path());
+}
@@ -1,23 +1,82 @@
 use crate::shared::*;
 use std::{
-    fs::{File, read_to_string},
-    io::{BufRead, BufReader, Lines},
+    fs::read_to_string,
+    io::Read,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
 };
 
-pub fn input_lines(day: u8) -> Result<std::io::Lines<BufReader<File>>> {
-    let path = format!("inputs/{}.txt", day);
+// Where puzzle input is read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    Path(PathBuf),
+    Stdin,
+}
 
-    match std::fs::File::open(&path) {
-        Ok(f) => Ok(BufReader::new(f).lines()),
-        Err(e) => Err(Error::from(format!("failed to open {path}: {e}"))),
+impl InputSource {
+    // Parses a command line argument, where "-" means stdin.
+    pub fn parse(s: &str) -> InputSource {
+        if s == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::Path(PathBuf::from(s))
+        }
     }
 }
 
-pub fn input(day: u8) -> Result<String> {
-    let path = format!("inputs/{}.txt", day);
+static INPUT_OVERRIDE: Mutex<Option<InputSource>> = Mutex::new(None);
+static STDIN: OnceLock<std::result::Result<String, String>> = OnceLock::new();
+
+// Makes input() read from the given source instead of the day's input file, regardless of which
+// day is asked for. Passing None restores the default.
+pub fn override_input(source: Option<InputSource>) {
+    *INPUT_OVERRIDE.lock().unwrap() = source;
+}
 
-    match read_to_string(&path) {
-        Ok(v) => Ok(v.trim_end_matches("\n").to_owned()),
-        Err(e) => err(&format!("failed to read file {path}: {e}")),
+// The path of a day's input file: inputs/{day}.txt, or {day}.txt in $AOC_INPUT_DIR if set.
+pub fn input_path(day: u8) -> PathBuf {
+    let dir = std::env::var_os("AOC_INPUT_DIR").unwrap_or_else(|| "inputs".into());
+    PathBuf::from(dir).join(format!("{day}.txt"))
+}
+
+fn input_source(day: u8) -> InputSource {
+    match &*INPUT_OVERRIDE.lock().unwrap() {
+        Some(source) => source.clone(),
+        None => InputSource::Path(input_path(day)),
     }
 }
+
+// Stdin can only be consumed once, so it is read in full on first use and kept for any later
+// readers (e.g. repeated benchmark runs).
+fn stdin() -> Result<String> {
+    let contents = STDIN.get_or_init(|| {
+        let mut buf = String::new();
+        match std::io::stdin().read_to_string(&mut buf) {
+            Ok(_) => Ok(buf),
+            Err(e) => Err(format!("failed to read stdin: {e}")),
+        }
+    });
+
+    contents.clone().map_err(Error::from)
+}
+
+pub fn input(day: u8) -> Result<String> {
+    let contents = match input_source(day) {
+        InputSource::Path(path) => match read_to_string(&path) {
+            Ok(v) => v,
+            Err(e) => return err(&format!("failed to read file {}: {e}", path.display())),
+        },
+        InputSource::Stdin => stdin()?,
+    };
+
+    Ok(contents.trim_end_matches("\n").to_owned())
+}
+
+#[test]
+fn test_input_source_parse() {
+    assert_eq!(InputSource::Stdin, InputSource::parse("-"));
+    assert_eq!(
+        InputSource::Path(PathBuf::from("examples/4.txt")),
+        InputSource::parse("examples/4.txt")
+    );
+}
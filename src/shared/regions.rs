@@ -0,0 +1,211 @@
+use crate::shared::*;
+
+// A connected region of cells sharing a char, and its shape. Perimeter and sides are always
+// measured along the edges of cells, whichever neighbourhood connected them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    pub c: char,
+    pub cells: Vec<Position>,
+    pub perimeter: usize,
+    // The number of straight sides of its outline, including those of any holes. Equal to the
+    // number of corners.
+    pub sides: usize,
+    // The top left and bottom right corners of its bounding box.
+    pub min: Position,
+    pub max: Position,
+}
+
+impl Region {
+    fn measure(c: char, cells: Vec<Position>, contains: impl Fn(Position) -> bool) -> Region {
+        let mut perimeter = 0;
+        let mut sides = 0;
+        let mut min = cells[0];
+        let mut max = cells[0];
+
+        for &pos in &cells {
+            perimeter += [UP, LEFT, RIGHT, DOWN]
+                .iter()
+                .filter(|d| !contains(pos + **d))
+                .count();
+
+            // Each corner of a cell is a corner of the outline if both of the cells along its
+            // edges are outside, or both are inside but the one diagonally across is not.
+            for (a, b) in [(UP, RIGHT), (RIGHT, DOWN), (DOWN, LEFT), (LEFT, UP)] {
+                let (a_in, b_in) = (contains(pos + a), contains(pos + b));
+                if (!a_in && !b_in) || (a_in && b_in && !contains(pos + a + b)) {
+                    sides += 1;
+                }
+            }
+
+            min = Position::at(min.x.min(pos.x), min.y.min(pos.y));
+            max = Position::at(max.x.max(pos.x), max.y.max(pos.y));
+        }
+
+        Region {
+            c,
+            cells,
+            perimeter,
+            sides,
+            min,
+            max,
+        }
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+// Every region of a grid, with each cell labelled with the index of its region.
+#[derive(Debug, Clone)]
+pub struct Components {
+    pub labels: MetaGrid<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl Components {
+    pub fn region_at(&self, pos: Position) -> &Region {
+        &self.regions[*self.labels.meta(pos)]
+    }
+}
+
+// Where a position lands on the grid, going round the edges if it wraps.
+fn locate(grid: &CharGrid, pos: Position) -> Position {
+    if grid.wraps() { grid.wrap(pos) } else { pos }
+}
+
+// Collects the cells connected to start through cells with its char, in the order they were
+// reached.
+fn fill(
+    grid: &CharGrid,
+    start: Position,
+    neighbourhood: Neighbourhood,
+    seen: &mut BitGrid,
+) -> Vec<Position> {
+    let c = grid.at(start);
+    let mut cells = Vec::new();
+    let mut stack = vec![start];
+    seen.set(start, true);
+
+    while let Some(pos) = stack.pop() {
+        cells.push(pos);
+
+        for next in grid.neighbours(pos, neighbourhood) {
+            if grid.at(next) == c && !seen.at(next) {
+                seen.set(next, true);
+                stack.push(next);
+            }
+        }
+    }
+
+    cells
+}
+
+// The region of cells connected to a position through cells with the same char.
+pub fn flood_fill(
+    grid: &impl AsRef<CharGrid>,
+    start: Position,
+    neighbourhood: Neighbourhood,
+) -> Region {
+    let grid = grid.as_ref();
+    let mut seen = BitGrid::new(grid.width(), grid.height());
+
+    let cells = fill(grid, start, neighbourhood, &mut seen);
+    Region::measure(grid.at(start), cells, |p| {
+        seen.at_checked(locate(grid, p)) == Some(true)
+    })
+}
+
+// Splits a grid into its regions, numbered in the order their first cells appear.
+pub fn label(grid: &impl AsRef<CharGrid>, neighbourhood: Neighbourhood) -> Components {
+    let grid = grid.as_ref();
+    let mut labels = MetaGrid::of(grid.clone());
+    let mut seen = BitGrid::new(grid.width(), grid.height());
+    let mut regions = Vec::new();
+
+    for pos in grid.positions() {
+        if seen.at(pos) {
+            continue;
+        }
+
+        let id = regions.len();
+        let cells = fill(grid, pos, neighbourhood, &mut seen);
+        for &cell in &cells {
+            labels.set_meta(cell, id);
+        }
+
+        // Cells not seen yet are unlabelled, which looks the same as the first label.
+        let contains = |p: Position| {
+            let p = locate(grid, p);
+            seen.at_checked(p) == Some(true) && *labels.meta(p) == id
+        };
+        regions.push(Region::measure(grid.at(pos), cells, contains));
+    }
+
+    Components { labels, regions }
+}
+
+#[test]
+fn test_flood_fill() {
+    let grid = CharGrid::from_str("AAB\nABA\nBAA").unwrap();
+
+    let region = flood_fill(&grid, Position::at(0, 0), Neighbourhood::Four);
+    assert_eq!(3, region.area());
+    assert_eq!(8, region.perimeter);
+    assert_eq!(
+        (Position::at(0, 0), Position::at(1, 1)),
+        (region.min, region.max)
+    );
+
+    let region = flood_fill(&grid, Position::at(0, 0), Neighbourhood::Eight);
+    assert_eq!(6, region.area());
+    assert_eq!(16, region.perimeter);
+}
+
+#[test]
+fn test_label() {
+    // The region sizes, perimeters and sides of the examples of 2024 day 12.
+    let grid = CharGrid::from_str("AAAA\nBBCD\nBBCC\nEEEC").unwrap();
+    let components = label(&grid, Neighbourhood::Four);
+
+    let shapes: Vec<_> = components
+        .regions
+        .iter()
+        .map(|r| (r.c, r.area(), r.perimeter, r.sides))
+        .collect();
+    assert_eq!(
+        vec![
+            ('A', 4, 10, 4),
+            ('B', 4, 8, 4),
+            ('C', 4, 10, 8),
+            ('D', 1, 4, 4),
+            ('E', 3, 8, 4),
+        ],
+        shapes
+    );
+    assert_eq!('C', components.region_at(Position::at(3, 3)).c);
+
+    // O surrounds four separate X regions, and the holes they leave add to its sides.
+    let grid = CharGrid::from_str("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO").unwrap();
+    let components = label(&grid, Neighbourhood::Four);
+    assert_eq!(5, components.regions.len());
+    assert_eq!((21, 36, 20), {
+        let o = &components.regions[0];
+        (o.area(), o.perimeter, o.sides)
+    });
+}
+
+#[test]
+fn test_wrapping_regions() {
+    // The four corners are one 2x2 square once the edges wrap.
+    let grid = CharGrid::from_str("A.A\n...\nA.A").unwrap();
+    assert_eq!(4, label(&grid, Neighbourhood::Four).regions.len() - 1);
+
+    let grid = grid.wrapping();
+    let region = flood_fill(&grid, Position::at(0, 0), Neighbourhood::Four);
+    assert_eq!((4, 8, 4), (region.area(), region.perimeter, region.sides));
+
+    let components = label(&grid, Neighbourhood::Four);
+    assert_eq!(2, components.regions.len());
+    assert_eq!(0, *components.labels.meta(Position::at(2, 2)));
+}
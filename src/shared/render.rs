@@ -0,0 +1,298 @@
+use crate::shared::visualize::Frame;
+use crate::shared::*;
+use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Grey,
+    // One of the 256 colours of an xterm-compatible terminal.
+    Ansi(u8),
+}
+
+impl Color {
+    // The SGR parameters selecting this colour, as a foreground colour if fg is set and a
+    // background colour otherwise.
+    fn sgr(&self, fg: bool) -> String {
+        let base = if fg { 30 } else { 40 };
+        match self {
+            Color::Black => base.to_string(),
+            Color::Red => (base + 1).to_string(),
+            Color::Green => (base + 2).to_string(),
+            Color::Yellow => (base + 3).to_string(),
+            Color::Blue => (base + 4).to_string(),
+            Color::Magenta => (base + 5).to_string(),
+            Color::Cyan => (base + 6).to_string(),
+            Color::White => (base + 7).to_string(),
+            Color::Grey => (base + 60).to_string(),
+            Color::Ansi(n) => format!("{};5;{n}", base + 8),
+        }
+    }
+}
+
+// How a cell is drawn. Unset fields fall back to the terminal's defaults.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub fn fg(color: Color) -> Style {
+        Style {
+            fg: Some(color),
+            ..Default::default()
+        }
+    }
+
+    pub fn bg(color: Color) -> Style {
+        Style {
+            bg: Some(color),
+            ..Default::default()
+        }
+    }
+
+    pub fn on(self, color: Color) -> Style {
+        Style {
+            bg: Some(color),
+            ..self
+        }
+    }
+
+    pub fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    pub fn reverse(self) -> Style {
+        Style {
+            reverse: true,
+            ..self
+        }
+    }
+
+    // Layers another style over this one, keeping any fields the other leaves unset.
+    fn over(self, other: Style) -> Style {
+        Style {
+            fg: other.fg.or(self.fg),
+            bg: other.bg.or(self.bg),
+            bold: self.bold || other.bold,
+            reverse: self.reverse || other.reverse,
+        }
+    }
+
+    fn escape(&self) -> String {
+        let mut params = vec!["0".to_owned()];
+        if self.bold {
+            params.push("1".to_owned());
+        }
+        if self.reverse {
+            params.push("7".to_owned());
+        }
+        if let Some(fg) = self.fg {
+            params.push(fg.sgr(true));
+        }
+        if let Some(bg) = self.bg {
+            params.push(bg.sgr(false));
+        }
+
+        format!("\x1b[{}m", params.join(";"))
+    }
+}
+
+// Metadata which can be shown as a heatmap.
+pub trait Heat {
+    fn heat(&self) -> f64;
+}
+
+macro_rules! impl_heat {
+    ($($t:ty),*) => {
+        $(impl Heat for $t {
+            fn heat(&self) -> f64 {
+                *self as f64
+            }
+        })*
+    };
+}
+
+impl_heat!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+// Cells with no value, like those a search never reached, have no heat.
+impl<T: Heat> Heat for Option<T> {
+    fn heat(&self) -> f64 {
+        self.as_ref().map_or(0.0, T::heat)
+    }
+}
+
+// Background colours for a heatmap, from coldest to hottest.
+const HEAT_RAMP: [u8; 12] = [17, 18, 19, 25, 31, 37, 71, 107, 143, 179, 208, 196];
+
+// Draws grids in colour. Cells are styled by their char, then by any highlighted sets of
+// positions they are in, with later highlights drawn over earlier ones.
+//
+//     let path = ...;
+//     let renderer = Renderer::new()
+//         .style('#', Style::fg(Color::Grey))
+//         .highlight(path, Style::bg(Color::Blue));
+//     print!("{}", renderer.draw(&grid));
+//
+// Colour is only used when stdout is a terminal, unless set with color(); otherwise grids are
+// drawn as plain text, like CharGrid::draw.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    styles: HashMap<char, Style>,
+    highlights: Vec<(HashSet<Position>, Style)>,
+    color: bool,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new()
+    }
+}
+
+impl Renderer {
+    pub fn new() -> Renderer {
+        Renderer {
+            styles: HashMap::new(),
+            highlights: Vec::new(),
+            color: std::io::stdout().is_terminal(),
+        }
+    }
+
+    pub fn style(mut self, c: char, style: Style) -> Renderer {
+        self.styles.insert(c, style);
+        self
+    }
+
+    pub fn highlight(
+        mut self,
+        positions: impl IntoIterator<Item = Position>,
+        style: Style,
+    ) -> Renderer {
+        self.highlights
+            .push((positions.into_iter().collect(), style));
+        self
+    }
+
+    pub fn color(mut self, color: bool) -> Renderer {
+        self.color = color;
+        self
+    }
+
+    fn cell_style(&self, pos: Position, c: char) -> Style {
+        let mut style = self.styles.get(&c).copied().unwrap_or_default();
+        for (positions, highlight) in &self.highlights {
+            if positions.contains(&pos) {
+                style = style.over(*highlight);
+            }
+        }
+        style
+    }
+
+    // Draws every cell with the style from style_at, only emitting escapes where the style
+    // changes.
+    fn draw_with(&self, grid: &CharGrid, style_at: impl Fn(Position, char) -> Style) -> String {
+        let mut str = String::new();
+
+        for y in 0..grid.height() {
+            let mut current = Style::default();
+            for x in 0..grid.width() {
+                let c = grid.at_xy(x, y);
+                if self.color {
+                    let style = style_at(Position::at(x, y), c);
+                    if style != current {
+                        str.push_str(&style.escape());
+                        current = style;
+                    }
+                }
+                str.push(c);
+            }
+            if current != Style::default() {
+                str.push_str("\x1b[0m");
+            }
+            str.push('\n');
+        }
+        str.push('\n');
+
+        str
+    }
+
+    pub fn draw(&self, grid: &impl Frame) -> String {
+        self.draw_with(&grid.to_frame(), |pos, c| self.cell_style(pos, c))
+    }
+
+    // Draws a grid with each cell's background coloured by its metadata, scaled between the
+    // smallest and largest non-zero values. Cells with no heat are left uncoloured.
+    pub fn heatmap<M: Metadata + Heat>(&self, grid: &MetaGrid<M>) -> String {
+        let heats = (0..grid.height())
+            .flat_map(|y| (0..grid.width()).map(move |x| grid.meta(Position::at(x, y)).heat()))
+            .filter(|h| *h != 0.0);
+        let (min, max) = heats.fold((f64::MAX, f64::MIN), |(lo, hi), h| (lo.min(h), hi.max(h)));
+
+        self.draw_with(grid.chars(), |pos, c| {
+            let heat = grid.meta(pos).heat();
+            let style = self.cell_style(pos, c);
+            if heat == 0.0 || style.bg.is_some() {
+                return style;
+            }
+
+            let scaled = if max > min {
+                (heat - min) / (max - min)
+            } else {
+                1.0
+            };
+            let idx = (scaled * (HEAT_RAMP.len() - 1) as f64).round() as usize;
+            style.on(Color::Ansi(HEAT_RAMP[idx]))
+        })
+    }
+}
+
+#[test]
+fn test_plain() {
+    let cg = CharGrid::from_str("#.\n.#").unwrap();
+    let renderer = Renderer::new()
+        .style('#', Style::fg(Color::Red))
+        .color(false);
+
+    assert_eq!(cg.draw(), renderer.draw(&cg));
+}
+
+#[test]
+fn test_styles() {
+    let cg = CharGrid::from_str("##.\n...").unwrap();
+    let renderer = Renderer::new()
+        .style('#', Style::fg(Color::Red).bold())
+        .highlight(
+            [Position::at(1, 0), Position::at(2, 0)],
+            Style::bg(Color::Ansi(21)),
+        )
+        .color(true);
+
+    assert_eq!(
+        "\x1b[0;1;31m#\x1b[0;1;31;48;5;21m#\x1b[0;48;5;21m.\x1b[0m\n...\n\n",
+        renderer.draw(&cg)
+    );
+}
+
+#[test]
+fn test_heatmap() {
+    let mut mg: MetaGrid<usize> = MetaGrid::from_str("abc").unwrap();
+    mg.set_meta(Position::at(1, 0), 1);
+    mg.set_meta(Position::at(2, 0), 5);
+
+    assert_eq!(
+        "a\x1b[0;48;5;17mb\x1b[0;48;5;196mc\x1b[0m\n\n",
+        Renderer::new().color(true).heatmap(&mg)
+    );
+    assert_eq!("abc\n\n", Renderer::new().color(false).heatmap(&mg));
+}
@@ -0,0 +1,274 @@
+use crate::shared::*;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+// An unbounded grid which only stores the cells that differ from a background value, so it can
+// grow in any direction and hold negative or far apart positions. Its bounds are the smallest
+// rectangle around the stored cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Position, T>,
+    background: T,
+    // The top left and bottom right corners of the stored cells, if there are any.
+    bounds: Option<(Position, Position)>,
+}
+
+fn extend(bounds: Option<(Position, Position)>, pos: Position) -> Option<(Position, Position)> {
+    Some(match bounds {
+        None => (pos, pos),
+        Some((min, max)) => (
+            Position::at(min.x.min(pos.x), min.y.min(pos.y)),
+            Position::at(max.x.max(pos.x), max.y.max(pos.y)),
+        ),
+    })
+}
+
+impl<T: Clone + PartialEq> SparseGrid<T> {
+    pub fn new(background: T) -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+            background,
+            bounds: None,
+        }
+    }
+
+    // The cells of a dense grid which differ from the background, at the same positions.
+    pub fn from_grid<S: Storage<T>>(grid: &Grid<T, S>, background: T) -> SparseGrid<T> {
+        SparseGrid::from_grid_at(grid, Position::at(0, 0), background)
+    }
+
+    // The cells of a dense grid which differ from the background, with its top left cell at
+    // origin. The reverse of to_grid.
+    pub fn from_grid_at<S: Storage<T>>(
+        grid: &Grid<T, S>,
+        origin: Position,
+        background: T,
+    ) -> SparseGrid<T> {
+        let mut sparse = SparseGrid::new(background);
+        for (pos, v) in grid.iter() {
+            sparse.set(pos + origin, v);
+        }
+
+        sparse
+    }
+
+    pub fn background(&self) -> T {
+        self.background.clone()
+    }
+
+    // The number of cells which differ from the background.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn bounds(&self) -> Option<(Position, Position)> {
+        self.bounds
+    }
+
+    // The width and height of the bounds, which can be more than an i32 holds when cells are
+    // near both ends of its range.
+    pub fn width(&self) -> i64 {
+        self.bounds
+            .map_or(0, |(min, max)| max.x as i64 - min.x as i64 + 1)
+    }
+
+    pub fn height(&self) -> i64 {
+        self.bounds
+            .map_or(0, |(min, max)| max.y as i64 - min.y as i64 + 1)
+    }
+
+    pub fn at(&self, pos: Position) -> T {
+        self.cells.get(&pos).unwrap_or(&self.background).clone()
+    }
+
+    pub fn at_xy(&self, x: i32, y: i32) -> T {
+        self.at(Position::at(x, y))
+    }
+
+    // Setting a cell to the background removes it.
+    pub fn set(&mut self, pos: Position, v: T) {
+        if v == self.background {
+            self.remove(pos);
+            return;
+        }
+
+        self.cells.insert(pos, v);
+        self.bounds = extend(self.bounds, pos);
+    }
+
+    pub fn set_xy(&mut self, x: i32, y: i32, v: T) {
+        self.set(Position::at(x, y), v)
+    }
+
+    // Resets a cell to the background, returning what it held.
+    pub fn remove(&mut self, pos: Position) -> Option<T> {
+        let v = self.cells.remove(&pos)?;
+
+        // The bounds only shrink if the cell was on their edge.
+        if let Some((min, max)) = self.bounds
+            && (pos.x == min.x || pos.y == min.y || pos.x == max.x || pos.y == max.y)
+        {
+            self.bounds = self.cells.keys().fold(None, |bounds, &p| extend(bounds, p));
+        }
+
+        Some(v)
+    }
+
+    // The stored cells, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> + '_ {
+        self.cells.iter().map(|(p, v)| (*p, v))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.cells.keys().copied()
+    }
+
+    // Every neighbour of a position. There are no edges, so none are left out.
+    pub fn neighbours(
+        &self,
+        pos: Position,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = Position> + use<T> {
+        neighbourhood.directions().iter().map(move |d| pos + *d)
+    }
+
+    pub fn count_neighbours(
+        &self,
+        pos: Position,
+        neighbourhood: Neighbourhood,
+        pred: impl Fn(T) -> bool,
+    ) -> usize {
+        self.neighbours(pos, neighbourhood)
+            .filter(|p| pred(self.at(*p)))
+            .count()
+    }
+
+    // A dense grid covering the bounds, along with the position its top left cell came from, so
+    // that a dense position p is p + origin here. Panics if the bounds are too large for a Grid.
+    pub fn to_grid<S: Storage<T>>(&self) -> (Position, Grid<T, S>) {
+        let origin = self.bounds.map_or(Position::at(0, 0), |(min, _)| min);
+        let (Ok(width), Ok(height)) = (i32::try_from(self.width()), i32::try_from(self.height()))
+        else {
+            panic!(
+                "{}x{} is too large for a dense grid",
+                self.width(),
+                self.height()
+            );
+        };
+        let grid = Grid::from_fn(width, height, |p| self.at(p + origin));
+        (origin, grid)
+    }
+}
+
+impl<T: Clone + PartialEq + Display> SparseGrid<T> {
+    // Draws the cells within the bounds, in the same format as Grid::draw.
+    pub fn draw(&self) -> String {
+        let mut str = String::new();
+
+        if let Some((min, max)) = self.bounds {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    str.push_str(&self.at_xy(x, y).to_string())
+                }
+                str.push('\n')
+            }
+        }
+        str.push('\n');
+
+        str
+    }
+}
+
+impl<T: Clone + PartialEq, S: Storage<T>> Grid<T, S> {
+    pub fn to_sparse(&self, background: T) -> SparseGrid<T> {
+        SparseGrid::from_grid(self, background)
+    }
+}
+
+#[test]
+fn test_set_and_bounds() {
+    let mut grid = SparseGrid::new('.');
+    assert_eq!(None, grid.bounds());
+    assert_eq!("\n", grid.draw());
+
+    grid.set_xy(-2, 1, '#');
+    grid.set_xy(1_000_000, -3, '#');
+    assert_eq!(2, grid.len());
+    assert_eq!(
+        Some((Position::at(-2, -3), Position::at(1_000_000, 1))),
+        grid.bounds()
+    );
+    assert_eq!('#', grid.at_xy(-2, 1));
+    assert_eq!('.', grid.at_xy(5, 5));
+
+    // Clearing a cell on the edge shrinks the bounds.
+    grid.set_xy(1_000_000, -3, '.');
+    assert_eq!(1, grid.len());
+    assert_eq!(
+        Some((Position::at(-2, 1), Position::at(-2, 1))),
+        grid.bounds()
+    );
+    assert_eq!(Some('#'), grid.remove(Position::at(-2, 1)));
+    assert!(grid.is_empty());
+    assert_eq!(None, grid.bounds());
+
+    // Cells at both ends of the coordinate range are further apart than an i32 holds.
+    grid.set_xy(i32::MIN, i32::MIN, '#');
+    grid.set_xy(i32::MAX, i32::MAX, '#');
+    assert_eq!(1 << 32, grid.width());
+    assert_eq!(1 << 32, grid.height());
+}
+
+#[test]
+fn test_neighbours_and_draw() {
+    let mut grid = SparseGrid::new('.');
+    for (x, y) in [(-1, -1), (0, -1), (1, 1)] {
+        grid.set_xy(x, y, '#');
+    }
+
+    assert_eq!(
+        8,
+        grid.neighbours(Position::at(0, 0), Neighbourhood::Eight)
+            .count()
+    );
+    assert_eq!(
+        3,
+        grid.count_neighbours(Position::at(0, 0), Neighbourhood::Eight, |c| c == '#')
+    );
+    assert_eq!("##.\n...\n..#\n\n", grid.draw());
+}
+
+#[test]
+fn test_dense_conversion() {
+    let dense = CharGrid::from_str("..#\n#..\n...").unwrap();
+
+    let sparse = dense.to_sparse('.');
+    assert_eq!(2, sparse.len());
+    assert_eq!(
+        Some((Position::at(0, 0), Position::at(2, 1))),
+        sparse.bounds()
+    );
+
+    // Back to dense, cropped to the bounds.
+    let (origin, cropped): (Position, CharGrid) = sparse.to_grid();
+    assert_eq!(Position::at(0, 0), origin);
+    assert_eq!("..#\n#..\n\n", cropped.draw());
+    assert_eq!(sparse, cropped.to_sparse('.'));
+}
+
+#[test]
+fn test_dense_roundtrip_negative() {
+    let mut sparse = SparseGrid::new('.');
+    sparse.set_xy(-3, -2, '#');
+    sparse.set_xy(-1, 0, '@');
+
+    let (origin, dense): (Position, CharGrid) = sparse.to_grid();
+    assert_eq!(Position::at(-3, -2), origin);
+    assert_eq!("#..\n...\n..@\n\n", dense.draw());
+
+    assert_eq!(sparse, SparseGrid::from_grid_at(&dense, origin, '.'));
+}
@@ -0,0 +1,353 @@
+use crate::shared::*;
+
+impl<T, S: Storage<T>> Grid<T, S> {
+    // Builds a grid from this one by calling f for each position, wrapping if this one does.
+    fn transformed(&self, width: i32, height: i32, f: impl FnMut(Position) -> T) -> Grid<T, S> {
+        let grid = Grid::from_fn(width, height, f);
+        if self.wraps() { grid.wrapping() } else { grid }
+    }
+
+    // Turns the grid a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> Grid<T, S> {
+        let y_max = self.y_max();
+        self.transformed(self.height(), self.width(), |p| {
+            self.at_xy(p.y, y_max - p.x)
+        })
+    }
+
+    pub fn rotate_180(&self) -> Grid<T, S> {
+        let (x_max, y_max) = (self.x_max(), self.y_max());
+        self.transformed(self.width(), self.height(), |p| {
+            self.at_xy(x_max - p.x, y_max - p.y)
+        })
+    }
+
+    // Turns the grid a quarter turn anticlockwise.
+    pub fn rotate_ccw(&self) -> Grid<T, S> {
+        let x_max = self.x_max();
+        self.transformed(self.height(), self.width(), |p| {
+            self.at_xy(x_max - p.y, p.x)
+        })
+    }
+
+    // Mirrors the grid left to right.
+    pub fn flip_horizontal(&self) -> Grid<T, S> {
+        let x_max = self.x_max();
+        self.transformed(self.width(), self.height(), |p| {
+            self.at_xy(x_max - p.x, p.y)
+        })
+    }
+
+    // Mirrors the grid top to bottom.
+    pub fn flip_vertical(&self) -> Grid<T, S> {
+        let y_max = self.y_max();
+        self.transformed(self.width(), self.height(), |p| {
+            self.at_xy(p.x, y_max - p.y)
+        })
+    }
+
+    // Mirrors the grid along the diagonal from the top left.
+    pub fn transpose(&self) -> Grid<T, S> {
+        self.transformed(self.height(), self.width(), |p| self.at_xy(p.y, p.x))
+    }
+
+    // The grid in each of its 8 orientations: the four rotations, then the four rotations of its
+    // mirror image. Symmetric grids will have duplicates.
+    pub fn orientations(&self) -> Vec<Grid<T, S>> {
+        let flipped = self.flip_horizontal();
+        let flipped_rotations = [
+            flipped.rotate_cw(),
+            flipped.rotate_180(),
+            flipped.rotate_ccw(),
+        ];
+
+        let mut orientations = vec![
+            self.transformed(self.width(), self.height(), |p| self.at(p)),
+            self.rotate_cw(),
+            self.rotate_180(),
+            self.rotate_ccw(),
+            flipped,
+        ];
+        orientations.extend(flipped_rotations);
+        orientations
+    }
+
+    // A rectangle of the grid, with its top left corner at min.
+    pub fn crop(&self, min: Position, width: i32, height: i32) -> Result<Grid<T, S>> {
+        let max = min + Position::at(width - 1, height - 1);
+        if width < 0
+            || height < 0
+            || (width * height > 0 && !(self.in_bounds(min) && self.in_bounds(max)))
+        {
+            return err(&format!(
+                "cannot crop {width}x{height} at {min} from {}x{} grid",
+                self.width(),
+                self.height()
+            ));
+        }
+
+        Ok(self.transformed(width, height, |p| self.at(p + min)))
+    }
+
+    // Repeats the grid across and down.
+    pub fn tile(&self, across: i32, down: i32) -> Grid<T, S> {
+        let (width, height) = (self.width(), self.height());
+        self.transformed(width * across, height * down, |p| {
+            self.at_xy(p.x % width, p.y % height)
+        })
+    }
+
+    // Builds a grid from several, which wraps if they all do.
+    fn joined(
+        grids: &[Grid<T, S>],
+        width: i32,
+        height: i32,
+        f: impl FnMut(Position) -> T,
+    ) -> Grid<T, S> {
+        match grids.first() {
+            Some(first) if grids.iter().all(Grid::wraps) => first.transformed(width, height, f),
+            _ => Grid::from_fn(width, height, f),
+        }
+    }
+
+    // Joins grids of the same height side by side.
+    pub fn concat_horizontal(grids: &[Grid<T, S>]) -> Result<Grid<T, S>> {
+        let height = grids.first().map_or(0, |g| g.height());
+        if grids.iter().any(|g| g.height() != height) {
+            return err("grids joined side by side must have the same height");
+        }
+
+        let mut columns = Vec::new();
+        for (idx, grid) in grids.iter().enumerate() {
+            columns.extend((0..grid.width()).map(|x| (idx, x)));
+        }
+
+        Ok(Grid::joined(grids, columns.len() as i32, height, |p| {
+            let (idx, x) = columns[p.x as usize];
+            grids[idx].at_xy(x, p.y)
+        }))
+    }
+
+    // Stacks grids of the same width one above the other.
+    pub fn concat_vertical(grids: &[Grid<T, S>]) -> Result<Grid<T, S>> {
+        let width = grids.first().map_or(0, |g| g.width());
+        if grids.iter().any(|g| g.width() != width) {
+            return err("grids stacked vertically must have the same width");
+        }
+
+        let mut rows = Vec::new();
+        for (idx, grid) in grids.iter().enumerate() {
+            rows.extend((0..grid.height()).map(|y| (idx, y)));
+        }
+
+        Ok(Grid::joined(grids, width, rows.len() as i32, |p| {
+            let (idx, y) = rows[p.y as usize];
+            grids[idx].at_xy(p.x, y)
+        }))
+    }
+}
+
+impl<T: Ord, S: Storage<T>> Grid<T, S> {
+    // The same orientation of any rotation or reflection of the grid, so that shapes can be
+    // compared or deduplicated whichever way up they were given. This is the orientation whose
+    // cells, read row by row, come first.
+    pub fn canonical(&self) -> Grid<T, S> {
+        let key = |g: &Grid<T, S>| {
+            (
+                g.height(),
+                g.width(),
+                g.positions().map(|p| g.at(p)).collect::<Vec<T>>(),
+            )
+        };
+
+        let mut orientations = self.orientations().into_iter();
+        let mut best = orientations.next().unwrap();
+        let mut best_key = key(&best);
+        for grid in orientations {
+            let grid_key = key(&grid);
+            if grid_key < best_key {
+                best = grid;
+                best_key = grid_key;
+            }
+        }
+
+        best
+    }
+}
+
+// The chars and metadata of several MetaGrids, to be joined separately.
+fn split<M: Metadata>(grids: &[MetaGrid<M>]) -> (Vec<CharGrid>, Vec<Grid<M>>) {
+    grids
+        .iter()
+        .map(|g| (g.chars().clone(), g.metas().clone()))
+        .unzip()
+}
+
+// MetaGrids transform their chars and metadata together.
+macro_rules! meta_transforms {
+    ($($name:ident),*) => {
+        impl<M: Metadata> MetaGrid<M> {
+            $(pub fn $name(&self) -> MetaGrid<M> {
+                MetaGrid::from_parts(self.chars().$name(), self.metas().$name())
+            })*
+
+            pub fn orientations(&self) -> Vec<MetaGrid<M>> {
+                let chars = self.chars().orientations();
+                let metas = self.metas().orientations();
+                chars.into_iter().zip(metas).map(|(c, m)| MetaGrid::from_parts(c, m)).collect()
+            }
+
+            pub fn crop(&self, min: Position, width: i32, height: i32) -> Result<MetaGrid<M>> {
+                Ok(MetaGrid::from_parts(
+                    self.chars().crop(min, width, height)?,
+                    self.metas().crop(min, width, height)?,
+                ))
+            }
+
+            pub fn tile(&self, across: i32, down: i32) -> MetaGrid<M> {
+                MetaGrid::from_parts(self.chars().tile(across, down), self.metas().tile(across, down))
+            }
+
+            pub fn concat_horizontal(grids: &[MetaGrid<M>]) -> Result<MetaGrid<M>> {
+                let (chars, metas) = split(grids);
+                Ok(MetaGrid::from_parts(
+                    Grid::concat_horizontal(&chars)?,
+                    Grid::concat_horizontal(&metas)?,
+                ))
+            }
+
+            pub fn concat_vertical(grids: &[MetaGrid<M>]) -> Result<MetaGrid<M>> {
+                let (chars, metas) = split(grids);
+                Ok(MetaGrid::from_parts(
+                    Grid::concat_vertical(&chars)?,
+                    Grid::concat_vertical(&metas)?,
+                ))
+            }
+        }
+    };
+}
+
+meta_transforms!(
+    rotate_cw,
+    rotate_180,
+    rotate_ccw,
+    flip_horizontal,
+    flip_vertical,
+    transpose
+);
+
+#[test]
+fn test_rotate_and_flip() {
+    let grid = CharGrid::from_str("abc\ndef").unwrap();
+
+    assert_eq!("da\neb\nfc\n\n", grid.rotate_cw().draw());
+    assert_eq!("fed\ncba\n\n", grid.rotate_180().draw());
+    assert_eq!("cf\nbe\nad\n\n", grid.rotate_ccw().draw());
+    assert_eq!("cba\nfed\n\n", grid.flip_horizontal().draw());
+    assert_eq!("def\nabc\n\n", grid.flip_vertical().draw());
+    assert_eq!("ad\nbe\ncf\n\n", grid.transpose().draw());
+    assert_eq!(grid, grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw());
+    assert_eq!(grid.rotate_ccw(), grid.rotate_180().rotate_cw());
+
+    let orientations = grid.orientations();
+    assert_eq!(8, orientations.len());
+    assert!(orientations.contains(&grid.transpose()));
+    assert!(orientations.contains(&grid.flip_vertical()));
+}
+
+#[test]
+fn test_crop_and_tile() {
+    let grid = CharGrid::from_str("abc\ndef\nghi").unwrap();
+
+    assert_eq!(
+        "ef\nhi\n\n",
+        grid.crop(Position::at(1, 1), 2, 2).unwrap().draw()
+    );
+    assert!(grid.crop(Position::at(2, 2), 2, 1).is_err());
+
+    let tiled = CharGrid::from_str("ab").unwrap().tile(2, 2);
+    assert_eq!("abab\nabab\n\n", tiled.draw());
+
+    let a = CharGrid::from_str("a\nb").unwrap();
+    let b = CharGrid::from_str("cd\nef").unwrap();
+    assert_eq!(
+        "acd\nbef\n\n",
+        Grid::concat_horizontal(&[a.clone(), b.clone()])
+            .unwrap()
+            .draw()
+    );
+    assert_eq!(
+        "cd\nef\ncd\nef\n\n",
+        Grid::concat_vertical(&[b.clone(), b.clone()])
+            .unwrap()
+            .draw()
+    );
+    assert!(Grid::concat_vertical(&[a, b]).is_err());
+}
+
+#[test]
+fn test_canonical() {
+    let l = CharGrid::from_str("#.\n#.\n##").unwrap();
+
+    for grid in l.orientations() {
+        assert_eq!(l.canonical(), grid.canonical());
+    }
+    assert_ne!(
+        l.canonical(),
+        CharGrid::from_str("##\n##").unwrap().canonical()
+    );
+}
+
+#[test]
+fn test_meta_transforms() {
+    let mut mg: MetaGrid<usize> = MetaGrid::from_str("ab\ncd").unwrap();
+    mg.set_meta(Position::at(0, 0), 7);
+
+    let rotated = mg.rotate_cw();
+    assert_eq!(('a', &7), rotated.at(Position::at(1, 0)));
+    assert_eq!(8, mg.orientations().len());
+    assert_eq!(28, mg.tile(2, 2).sum_meta());
+}
+
+#[test]
+fn test_transforms_keep_wrapping() {
+    let grid = CharGrid::from_str("abc\ndef").unwrap().wrapping();
+
+    let rotated = grid.rotate_cw();
+    assert!(rotated.wraps());
+    assert_eq!(
+        Some(Position::at(0, 0)),
+        rotated.step(Position::at(0, 2), DOWN)
+    );
+    assert_eq!(grid, rotated.rotate_ccw());
+    assert!(grid.orientations().iter().all(|g| g.wraps()));
+    assert!(grid.canonical().wraps());
+    assert!(grid.crop(Position::at(1, 0), 2, 2).unwrap().wraps());
+    assert!(grid.tile(2, 2).wraps());
+    assert!(!CharGrid::from_str("ab").unwrap().rotate_cw().wraps());
+
+    let mg: MetaGrid<usize> = MetaGrid::from_str("ab\ncd").unwrap().wrapping();
+    assert!(mg.rotate_cw().wraps());
+    assert!(mg.rotate_cw().metas().wraps());
+}
+
+#[test]
+fn test_meta_concat() {
+    let mut a: MetaGrid<usize> = MetaGrid::from_str("ab").unwrap();
+    a.set_meta(Position::at(1, 0), 3);
+    let b: MetaGrid<usize> = MetaGrid::from_str("c").unwrap();
+
+    let joined = MetaGrid::concat_horizontal(&[a.clone(), b.clone()]).unwrap();
+    assert_eq!("abc\n\n", joined.chars().draw());
+    assert_eq!(('b', &3), joined.at(Position::at(1, 0)));
+    assert!(MetaGrid::concat_vertical(&[a.clone(), b.clone()]).is_err());
+
+    let stacked = MetaGrid::concat_vertical(&[a.clone().wrapping(), a.wrapping()]).unwrap();
+    assert_eq!(6, stacked.sum_meta());
+    assert!(stacked.wraps());
+    assert!(
+        !MetaGrid::concat_horizontal(&[b.clone().wrapping(), b])
+            .unwrap()
+            .wraps()
+    );
+}
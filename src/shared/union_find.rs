@@ -0,0 +1,143 @@
+// A disjoint-set forest over the items 0..len, for tracking which items have been joined into
+// the same set. Joining and finding take nearly constant time, thanks to union by size and path
+// compression. Items can be added as they are discovered.
+#[derive(Debug, Clone, Default)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    // The number of items in each set, kept up to date on its root.
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    // n items, each in a set of its own.
+    pub fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            sets: n,
+        }
+    }
+
+    // Adds an item in a set of its own, returning it.
+    pub fn add(&mut self) -> usize {
+        let item = self.parent.len();
+        self.parent.push(item);
+        self.size.push(1);
+        self.sets += 1;
+
+        item
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    // The number of separate sets.
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+
+    // The item representing the set an item is in. Every item on the way there is pointed
+    // straight at it, to shorten later searches.
+    pub fn find(&mut self, item: usize) -> usize {
+        let mut root = item;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut item = item;
+        while item != root {
+            item = std::mem::replace(&mut self.parent[item], root);
+        }
+
+        root
+    }
+
+    // Joins the sets two items are in, returning false if they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        // The smaller set goes under the larger, to keep the trees shallow.
+        let (small, large) = if self.size[a] < self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+        self.sets -= 1;
+
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    // The number of items in the same set as an item, including itself.
+    pub fn size(&mut self, item: usize) -> usize {
+        let root = self.find(item);
+        self.size[root]
+    }
+
+    // The items of each set, in order, with the sets ordered by their first item.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut groups = Vec::with_capacity(self.sets);
+        // Where each root's group is in groups, once it has been seen.
+        let mut group_of = vec![usize::MAX; self.len()];
+
+        for item in 0..self.len() {
+            let root = self.find(item);
+            if group_of[root] == usize::MAX {
+                group_of[root] = groups.len();
+                groups.push(Vec::with_capacity(self.size[root]));
+            }
+            groups[group_of[root]].push(item);
+        }
+
+        groups
+    }
+}
+
+#[test]
+fn test_union_find() {
+    let mut sets = UnionFind::new(6);
+    assert_eq!(6, sets.sets());
+
+    assert!(sets.union(0, 1));
+    assert!(sets.union(4, 5));
+    assert!(sets.union(1, 5));
+    assert!(!sets.union(0, 4));
+
+    assert!(sets.connected(0, 5));
+    assert!(!sets.connected(0, 2));
+    assert_eq!(4, sets.size(4));
+    assert_eq!(1, sets.size(3));
+    assert_eq!(3, sets.sets());
+    assert_eq!(vec![vec![0, 1, 4, 5], vec![2], vec![3]], sets.groups());
+}
+
+#[test]
+fn test_add() {
+    let mut sets = UnionFind::default();
+    assert!(sets.is_empty());
+
+    let a = sets.add();
+    let b = sets.add();
+    assert_eq!((0, 1), (a, b));
+    assert_eq!(2, sets.sets());
+
+    sets.union(a, b);
+    let c = sets.add();
+    assert!(sets.connected(a, b));
+    assert!(!sets.connected(a, c));
+    assert_eq!((3, 2), (sets.len(), sets.sets()));
+}
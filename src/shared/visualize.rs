@@ -0,0 +1,258 @@
+use crate::shared::*;
+use getch_rs::{Getch, Key};
+use std::cell::RefCell;
+use std::io::{IsTerminal, Write};
+use std::sync::mpsc::{RecvTimeoutError, channel};
+use std::time::Duration;
+
+// Anything that can be captured as a frame of a visualization.
+pub trait Frame {
+    fn to_frame(&self) -> CharGrid;
+}
+
+impl<G: AsRef<CharGrid>> Frame for G {
+    fn to_frame(&self) -> CharGrid {
+        self.as_ref().clone()
+    }
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<Vec<CharGrid>>> = const { RefCell::new(None) };
+}
+
+// Starts capturing the frames passed to record_frame on this thread.
+pub fn start_recording() {
+    RECORDING.with(|r| *r.borrow_mut() = Some(Vec::new()));
+}
+
+// Stops capturing frames, and returns those captured since start_recording.
+pub fn stop_recording() -> Vec<CharGrid> {
+    RECORDING.with(|r| r.borrow_mut().take().unwrap_or_default())
+}
+
+// Captures a frame if recording has been started, and otherwise does nothing. Simulations can
+// call this at each step at no cost outside of a recording.
+pub fn record_frame(grid: &impl Frame) {
+    RECORDING.with(|r| {
+        if let Some(frames) = r.borrow_mut().as_mut() {
+            frames.push(grid.to_frame());
+        }
+    });
+}
+
+// Draws a frame, highlighting (in reverse video) the cells which differ from the previous frame.
+pub fn draw_frame(prev: Option<&CharGrid>, grid: &CharGrid) -> String {
+    let same_size = |p: &&CharGrid| p.width() == grid.width() && p.height() == grid.height();
+    let prev = prev.filter(same_size);
+
+    let mut str = String::new();
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let c = grid.at_xy(x, y);
+            if prev.is_some_and(|p| p.at_xy(x, y) != c) {
+                str.push_str(&format!("\x1b[7m{c}\x1b[0m"));
+            } else {
+                str.push(c);
+            }
+        }
+        str.push('\n');
+    }
+
+    str
+}
+
+const HELP: &str = "left/right: step  space: play  +/-: speed  home/end  <n> enter: jump  q: quit";
+
+const QUIT_KEYS: [Key; 3] = [Key::Char('q'), Key::Esc, Key::Ctrl('c')];
+
+// The position and playback state of the visualizer.
+struct Player {
+    frame: usize,
+    frames: usize,
+    playing: bool,
+    delay: Duration,
+    // Digits typed so far for a jump to a frame number.
+    jump: String,
+}
+
+impl Player {
+    fn new(frames: usize) -> Player {
+        Player {
+            frame: 0,
+            frames,
+            playing: false,
+            delay: Duration::from_millis(200),
+            jump: String::new(),
+        }
+    }
+
+    fn last(&self) -> usize {
+        self.frames.saturating_sub(1)
+    }
+
+    // Applies a key press, returning false when the visualizer should close.
+    fn handle(&mut self, key: Key) -> bool {
+        match key {
+            Key::Right | Key::Char('l') => self.frame = (self.frame + 1).min(self.last()),
+            Key::Left | Key::Char('h') => self.frame = self.frame.saturating_sub(1),
+            Key::Home | Key::Char('g') => self.frame = 0,
+            Key::End | Key::Char('G') => self.frame = self.last(),
+            Key::Char(' ') | Key::Char('p') => self.playing = !self.playing,
+            Key::Char('+') => self.delay = (self.delay / 2).max(Duration::from_millis(10)),
+            Key::Char('-') => self.delay = (self.delay * 2).min(Duration::from_secs(5)),
+            Key::Char(c) if c.is_ascii_digit() => {
+                self.jump.push(c);
+                return true;
+            }
+            Key::Char('\r') => {
+                // Frames are numbered from 1 on screen.
+                if let Ok(n) = self.jump.parse::<usize>() {
+                    self.frame = n.saturating_sub(1).min(self.last());
+                }
+            }
+            key if QUIT_KEYS.contains(&key) => return false,
+            _ => {}
+        }
+
+        self.jump.clear();
+        true
+    }
+
+    // Advances playback by one frame, pausing at the end.
+    fn tick(&mut self) {
+        if self.frame < self.last() {
+            self.frame += 1;
+        } else {
+            self.playing = false;
+        }
+    }
+}
+
+// Shows a sequence of frames in the terminal, redrawing them in place, and lets you step through
+// them with the keyboard.
+pub fn visualize(frames: &[CharGrid]) -> Result<()> {
+    if frames.is_empty() {
+        return err("no frames to visualize");
+    }
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return err("visualizer needs to be run in a terminal");
+    }
+
+    // Keys are read on their own thread so that playback can continue while waiting for one. The
+    // thread owns the terminal's raw mode, and restores it when it sees a quit key.
+    let (keys, key_rx) = channel();
+    let reader = std::thread::spawn(move || {
+        let getch = Getch::new();
+        while let Ok(key) = getch.getch() {
+            let quit = QUIT_KEYS.contains(&key);
+            if keys.send(key).is_err() || quit {
+                break;
+            }
+        }
+    });
+
+    let mut player = Player::new(frames.len());
+    let mut stdout = std::io::stdout();
+    let mut result = Ok(());
+
+    loop {
+        let mut status = format!("frame {}/{}", player.frame + 1, frames.len());
+        if player.playing {
+            status.push_str(" (playing)");
+        }
+        if !player.jump.is_empty() {
+            status.push_str(&format!("  jump to {}", player.jump));
+        }
+
+        // Clear the screen and move the cursor home before each redraw.
+        let prev = player.frame.checked_sub(1).map(|i| &frames[i]);
+        let grid = draw_frame(prev, &frames[player.frame]);
+        let written = write!(stdout, "\x1b[2J\x1b[H{status}\n\n{grid}\n{HELP}\n")
+            .and_then(|_| stdout.flush());
+        if let Err(e) = written {
+            result = Err(e.into());
+            break;
+        }
+
+        let key = if player.playing {
+            match key_rx.recv_timeout(player.delay) {
+                Ok(key) => Some(key),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            match key_rx.recv() {
+                Ok(key) => Some(key),
+                Err(_) => break,
+            }
+        };
+
+        match key {
+            Some(key) => {
+                if !player.handle(key) {
+                    break;
+                }
+            }
+            None => player.tick(),
+        }
+    }
+
+    // The reader has to finish before returning, or the terminal is left in raw mode. With the
+    // receiver gone, it stops at the next key even if that isn't a quit key.
+    drop(key_rx);
+    _ = reader.join();
+    result
+}
+
+#[test]
+fn test_recording() {
+    let mut cg = CharGrid::from_str("..\n..").unwrap();
+
+    record_frame(&cg);
+    start_recording();
+    record_frame(&cg);
+    cg.set_xy(1, 1, '#');
+    record_frame(&cg);
+    let frames = stop_recording();
+    record_frame(&cg);
+
+    assert_eq!(2, frames.len());
+    assert_eq!('.', frames[0].at_xy(1, 1));
+    assert_eq!('#', frames[1].at_xy(1, 1));
+    assert!(stop_recording().is_empty());
+}
+
+#[test]
+fn test_draw_frame() {
+    let a = CharGrid::from_str("..\n..").unwrap();
+    let b = CharGrid::from_str(".#\n..").unwrap();
+
+    assert_eq!("..\n..\n", draw_frame(None, &a));
+    assert_eq!(".\x1b[7m#\x1b[0m\n..\n", draw_frame(Some(&a), &b));
+}
+
+#[test]
+fn test_player() {
+    let mut player = Player::new(20);
+
+    player.handle(Key::Left);
+    assert_eq!(0, player.frame);
+    player.handle(Key::Right);
+    assert_eq!(1, player.frame);
+
+    player.handle(Key::Char('1'));
+    player.handle(Key::Char('5'));
+    assert_eq!("15", player.jump);
+    player.handle(Key::Char('\r'));
+    assert_eq!(14, player.frame);
+    assert_eq!("", player.jump);
+
+    player.handle(Key::End);
+    assert_eq!(19, player.frame);
+    player.handle(Key::Char(' '));
+    assert!(player.playing);
+    player.tick();
+    assert!(!player.playing);
+
+    assert!(!player.handle(Key::Char('q')));
+}
@@ -0,0 +1,184 @@
+use crate::examples;
+use crate::registry::Registry;
+use crate::runner::{self, Input, Run};
+use crate::shared::*;
+use getch_rs::{Getch, Key};
+use std::io::{IsTerminal, Write};
+
+const HELP: &str = "up/down: day  left/right: part  tab: input  enter: run  q: quit";
+
+// The interactive runner: pick a day, part and input with the keyboard and run it.
+struct Tui<'a> {
+    registry: &'a Registry,
+    days: Vec<u32>,
+    selected: usize,
+    part: u32,
+    // The inputs available for the selected day: the puzzle input, then its examples.
+    inputs: Vec<Input>,
+    input: usize,
+    last: Option<Run>,
+}
+
+enum Action {
+    Redraw,
+    Run,
+    Quit,
+}
+
+impl Tui<'_> {
+    fn new(registry: &Registry) -> Tui<'_> {
+        let mut tui = Tui {
+            registry,
+            days: registry.keys().copied().collect(),
+            selected: 0,
+            part: 1,
+            inputs: Vec::new(),
+            input: 0,
+            last: None,
+        };
+        tui.select(0);
+        tui
+    }
+
+    fn day(&self) -> u32 {
+        self.days[self.selected]
+    }
+
+    fn select(&mut self, idx: usize) {
+        self.selected = idx.min(self.days.len().saturating_sub(1));
+
+        self.inputs = vec![Input::Puzzle];
+        if let Ok(names) = examples::names(self.day()) {
+            self.inputs.extend(names.into_iter().map(Input::Example));
+        }
+        self.input = 0;
+    }
+
+    fn handle(&mut self, key: Key) -> Action {
+        match key {
+            Key::Up | Key::Char('k') => self.select(self.selected.saturating_sub(1)),
+            Key::Down | Key::Char('j') => self.select(self.selected + 1),
+            Key::Left | Key::Char('h') | Key::Char('1') => self.part = 1,
+            Key::Right | Key::Char('l') | Key::Char('2') => self.part = 2,
+            Key::Char('\t') | Key::Char('e') => self.input = (self.input + 1) % self.inputs.len(),
+            Key::BackTab => self.input = (self.input + self.inputs.len() - 1) % self.inputs.len(),
+            Key::Char('\r') | Key::Char(' ') | Key::Char('r') => return Action::Run,
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return Action::Quit,
+            _ => {}
+        }
+
+        Action::Redraw
+    }
+
+    fn run(&mut self) {
+        let entry = &self.registry[&self.day()];
+        let which = &self.inputs[self.input];
+
+        self.last = Some(runner::quietly(|| {
+            runner::load_and_run(entry.solution.as_ref(), entry.number, self.part, which)
+        }));
+    }
+
+    fn draw(&self) -> String {
+        let mut str = String::new();
+
+        for (idx, day) in self.days.iter().enumerate() {
+            let entry = &self.registry[day];
+            let cursor = if idx == self.selected { '>' } else { ' ' };
+            let parts: String = [1, 2]
+                .map(|p| if entry.implemented(p) { '*' } else { '.' })
+                .iter()
+                .collect();
+            str.push_str(&format!("{cursor} {day:>2} {parts} {}\n", entry.title));
+        }
+
+        let input = match &self.inputs[self.input] {
+            Input::Puzzle => "puzzle".to_owned(),
+            Input::Example(name) => format!("example {name}"),
+        };
+        str.push_str(&format!(
+            "\nday {} part {}, {input} input\n\n",
+            self.day(),
+            self.part
+        ));
+
+        if let Some(run) = &self.last {
+            match &run.answer {
+                Some(answer) => {
+                    str.push_str(&format!("day {} part {}: {answer}\n", run.day, run.part))
+                }
+                None => str.push_str(&format!(
+                    "day {} part {}: {}\n",
+                    run.day,
+                    run.part,
+                    runner::describe(run)
+                )),
+            }
+            str.push_str(&format!(
+                "solved in {:.2?}, loaded in {:.2?}\n",
+                run.elapsed, run.load
+            ));
+        }
+
+        str.push_str(&format!("\n{HELP}\n"));
+        str
+    }
+}
+
+pub fn run(registry: &Registry) -> Result<()> {
+    if registry.is_empty() {
+        return err("no days are registered");
+    }
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return err("tui needs to be run in a terminal");
+    }
+
+    let getch = Getch::new();
+    let mut tui = Tui::new(registry);
+    let mut stdout = std::io::stdout();
+
+    loop {
+        // Clear the screen and move the cursor home before each redraw.
+        write!(stdout, "\x1b[2J\x1b[H{}", tui.draw())?;
+        stdout.flush()?;
+
+        match tui.handle(getch.getch()?) {
+            Action::Redraw => {}
+            Action::Run => tui.run(),
+            Action::Quit => return Ok(()),
+        }
+    }
+}
+
+#[test]
+fn test_handle() {
+    let registry = crate::registry();
+    let mut tui = Tui::new(&registry);
+
+    assert_eq!(1, tui.day());
+    tui.handle(Key::Up);
+    assert_eq!(1, tui.day());
+    tui.handle(Key::Down);
+    tui.handle(Key::Char('j'));
+    assert_eq!(3, tui.day());
+
+    tui.handle(Key::Right);
+    assert_eq!(2, tui.part);
+
+    assert_eq!(Input::Puzzle, tui.inputs[tui.input]);
+    tui.handle(Key::Char('\t'));
+    assert_eq!(Input::Example("example".to_owned()), tui.inputs[tui.input]);
+    tui.handle(Key::Char('\t'));
+    assert_eq!(Input::Puzzle, tui.inputs[tui.input]);
+
+    tui.handle(Key::Char('\t'));
+    assert!(matches!(tui.handle(Key::Char('\r')), Action::Run));
+    tui.run();
+    assert_eq!(
+        Some("3121910778619"),
+        tui.last.as_ref().unwrap().answer.as_deref()
+    );
+    assert!(tui.draw().contains("day 3 part 2: 3121910778619\n"));
+
+    assert!(matches!(tui.handle(Key::Char('q')), Action::Quit));
+}